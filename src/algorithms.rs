@@ -1,5 +1,6 @@
 //! Useful algorithms related to RSA.
 
+pub(crate) mod generate;
 pub(crate) mod modular;
 
 mod mgf;
@@ -8,4 +9,5 @@ pub(crate) mod oaep;
 pub(crate) mod pad;
 pub(crate) mod pkcs1v15;
 pub(crate) mod pss;
+pub(crate) mod random;
 pub(crate) mod rsa;
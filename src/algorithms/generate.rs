@@ -0,0 +1,405 @@
+//! Generate prime components for the RSA Private Key
+
+use core::ops::ControlFlow;
+
+use rand_core::CryptoRngCore;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::algorithms::modular::montgomery::type_bits;
+use crate::algorithms::random::{random_bits, random_nonzero_below};
+use crate::algorithms::rsa::{compute_modulus, compute_private_exponent_euler_totient};
+use crate::errors::{Error, Result};
+use crate::primality::{miller_rabin, small_prime_residues, strong_lucas, SMALL_PRIMES};
+use crate::traits::UnsignedModularInt;
+
+/// Candidates of fewer bits than this may be small primes themselves and are
+/// not sieved.
+const SIEVE_MIN_BITS: usize = 11;
+
+/// How far the sieve walks from a random starting point before drawing a new one.
+const SIEVE_MAX_DELTA: u32 = 1 << 16;
+
+/// Progress of a key generation, reported to the callback passed to
+/// [`RsaPrivateKey::new_with_progress`](crate::RsaPrivateKey::new_with_progress).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum KeyGenProgress {
+    /// A candidate passed the small-prime sieve and is about to be tested
+    /// for primality.
+    Candidate {
+        /// Index of the prime being searched for.
+        index: usize,
+    },
+    /// A primality test round passed for the current candidate. Rounds are
+    /// Miller-Rabin tests, followed by a final strong Lucas test.
+    Round {
+        /// Index of the prime being searched for.
+        index: usize,
+        /// Number of the round that passed, starting at zero.
+        round: usize,
+    },
+    /// A prime was found.
+    PrimeFound {
+        /// Index of the prime that was found.
+        index: usize,
+    },
+}
+
+pub(crate) struct RsaPrivateKeyComponents<T, const MAX_PRIMES: usize>
+where
+    T: UnsignedModularInt,
+{
+    pub(crate) e: T,
+    pub(crate) d: T,
+    pub(crate) primes: [T; MAX_PRIMES],
+    pub(crate) nprimes: usize,
+}
+
+/// Generates a multi-prime RSA keypair of the given bit size, public exponent,
+/// and the given random source, as suggested in [1]. Although the public
+/// keys are compatible (actually, indistinguishable) from the 2-prime case,
+/// the private keys are not. Thus it may not be possible to export multi-prime
+/// private keys in certain formats or to subsequently import them into other
+/// code.
+///
+/// At most `MAX_PRIMES` primes can be generated.
+///
+/// Table 1 in [2] suggests maximum numbers of primes for a given size.
+///
+/// [1]: https://patents.google.com/patent/US4405829A/en
+/// [2]: https://cacr.uwaterloo.ca/techreports/2006/cacr2006-16.pdf
+pub(crate) fn generate_multi_prime_key_with_exp<T, R, F, const MAX_PRIMES: usize>(
+    rng: &mut R,
+    nprimes: usize,
+    bit_size: usize,
+    exp: T,
+    progress: &mut F,
+) -> Result<RsaPrivateKeyComponents<T, MAX_PRIMES>>
+where
+    T: UnsignedModularInt,
+    R: CryptoRngCore + ?Sized,
+    F: FnMut(KeyGenProgress) -> ControlFlow<()>,
+{
+    if nprimes < 2 {
+        return Err(Error::NprimesTooSmall);
+    }
+    if nprimes > MAX_PRIMES {
+        return Err(Error::InvalidArguments);
+    }
+    if bit_size > type_bits::<T>() {
+        return Err(Error::ModulusTooLarge);
+    }
+    // An even exponent is never invertible modulo the totient.
+    if exp.is_even() || exp <= T::one() {
+        return Err(Error::InvalidExponent);
+    }
+
+    if bit_size < 64 {
+        let prime_bits = (bit_size / nprimes) as f64;
+        let prime_limit = (1u64 << (bit_size / nprimes)) as f64;
+        // pi aproximates the number of primes less than prime_limit
+        let mut pi = prime_limit / (prime_bits * core::f64::consts::LN_2 - 1f64);
+        // Generated primes start with 0b11, so we can only use a quarter of them.
+        pi /= 4f64;
+        // Use a factor of two to ensure that key generation terminates in a reasonable amount of time.
+        pi /= 2f64;
+
+        if pi < nprimes as f64 {
+            return Err(Error::TooFewPrimes);
+        }
+    }
+
+    let mut primes = Zeroizing::new([T::zero(); MAX_PRIMES]);
+
+    let d = loop {
+        let mut todo = bit_size;
+        // `generate_prime` sets the top two bits in each prime.
+        // Thus each prime has the form
+        //   p_i = 2^bitlen(p_i) × 0.11... (in base 2).
+        // And the product is:
+        //   P = 2^todo × α
+        // where α is the product of nprimes numbers of the form 0.11...
+        //
+        // If α < 1/2 (which can happen for nprimes > 2), we need to
+        // shift todo to compensate for lost bits: the mean value of 0.11...
+        // is 7/8, so todo + shift - nprimes * log2(7/8) ~= bits - 1/2
+        // will give good results.
+        if nprimes >= 7 {
+            todo += (nprimes - 2) / 5;
+        }
+
+        for (i, prime) in primes.iter_mut().take(nprimes).enumerate() {
+            *prime = generate_prime(rng, todo / (nprimes - i), i, progress)?;
+            todo -= prime.bits();
+        }
+
+        if let Some(d) = accept_primes(&primes[..nprimes], bit_size, &exp)? {
+            break d;
+        }
+        // Clear the rejected candidates before drawing new ones.
+        primes.zeroize();
+    };
+
+    Ok(RsaPrivateKeyComponents {
+        e: exp,
+        d,
+        primes: *primes,
+        nprimes,
+    })
+}
+
+/// Returns the private exponent for the candidate `primes`, or `None` if they
+/// are rejected: if two of them are equal, if the modulus is not `bit_size`
+/// bits long, or if `exp` is not invertible modulo the totient.
+fn accept_primes<T>(primes: &[T], bit_size: usize, exp: &T) -> Result<Option<T>>
+where
+    T: UnsignedModularInt,
+{
+    // Makes sure that primes is pairwise unequal.
+    for (i, prime1) in primes.iter().enumerate() {
+        if primes[..i].contains(prime1) {
+            return Ok(None);
+        }
+    }
+
+    let n = compute_modulus(primes)?;
+    if n.bits() != bit_size {
+        // This should never happen for nprimes == 2 because
+        // generate_prime sets the top two bits in each prime.
+        // For nprimes > 2 we hope it does not happen often.
+        return Ok(None);
+    }
+
+    Ok(compute_private_exponent_euler_totient(primes, exp).ok())
+}
+
+/// Passes `event` to the progress callback, turning a break into [`Error::Cancelled`].
+fn report<F>(progress: &mut F, event: KeyGenProgress) -> Result<()>
+where
+    F: FnMut(KeyGenProgress) -> ControlFlow<()>,
+{
+    match progress(event) {
+        ControlFlow::Continue(()) => Ok(()),
+        ControlFlow::Break(()) => Err(Error::Cancelled),
+    }
+}
+
+/// Generates a random prime of exactly `bits` bits, with the top two bits set.
+///
+/// A random odd starting point is drawn and then walked upwards in steps of
+/// two, using residues modulo [`SMALL_PRIMES`] to skip candidates with small
+/// factors. Candidates surviving the sieve are tested with Miller-Rabin.
+fn generate_prime<T, R, F>(rng: &mut R, bits: usize, index: usize, progress: &mut F) -> Result<T>
+where
+    T: UnsignedModularInt,
+    R: CryptoRngCore + ?Sized,
+    F: FnMut(KeyGenProgress) -> ControlFlow<()>,
+{
+    if bits < 3 {
+        return Err(Error::TooFewPrimes);
+    }
+    let two = T::one() + T::one();
+    let rounds = miller_rabin_rounds(bits);
+    let mut residues = [0u16; SMALL_PRIMES.len()];
+
+    loop {
+        let mut candidate: T = random_bits(rng, bits);
+        candidate = candidate | (T::one() << (bits - 1)) | (T::one() << (bits - 2)) | T::one();
+
+        let sieve = bits >= SIEVE_MIN_BITS;
+        if sieve {
+            small_prime_residues(&candidate, &mut residues);
+        }
+
+        let mut delta = 0u32;
+        while delta < SIEVE_MAX_DELTA {
+            if candidate.bits() != bits {
+                break;
+            }
+            let survives = !sieve
+                || residues
+                    .iter()
+                    .zip(SMALL_PRIMES.iter())
+                    .all(|(r, p)| (*r as u32 + delta) % *p as u32 != 0);
+            if survives {
+                report(progress, KeyGenProgress::Candidate { index })?;
+                if is_probable_prime(rng, &candidate, rounds, index, progress)? {
+                    residues.zeroize();
+                    report(progress, KeyGenProgress::PrimeFound { index })?;
+                    return Ok(candidate);
+                }
+            }
+            candidate = match candidate.checked_add(&two) {
+                Some(next) => next,
+                None => break,
+            };
+            delta += 2;
+        }
+        candidate.zeroize();
+    }
+}
+
+/// Number of Miller-Rabin rounds for a random candidate of `bits` bits, for an
+/// error probability below 2^-100 (FIPS 186-5, Appendix B.3). A strong Lucas
+/// test is run on top of these.
+fn miller_rabin_rounds(bits: usize) -> usize {
+    match bits {
+        1536.. => 4,
+        1024.. => 5,
+        512.. => 8,
+        _ => 20,
+    }
+}
+
+/// Tests a candidate that survived the sieve: Miller-Rabin with witness 2,
+/// `rounds - 1` further rounds with random witnesses and a strong Lucas test.
+///
+/// Every passed round is reported. `candidate` must be odd and at least 7.
+fn is_probable_prime<T, R, F>(
+    rng: &mut R,
+    candidate: &T,
+    rounds: usize,
+    index: usize,
+    progress: &mut F,
+) -> Result<bool>
+where
+    T: UnsignedModularInt,
+    R: CryptoRngCore + ?Sized,
+    F: FnMut(KeyGenProgress) -> ControlFlow<()>,
+{
+    let two = T::one() + T::one();
+    if !miller_rabin(candidate, &two) {
+        return Ok(false);
+    }
+    report(progress, KeyGenProgress::Round { index, round: 0 })?;
+
+    // Witnesses are drawn from [2, candidate - 2].
+    let bound = *candidate - two;
+    for round in 1..rounds {
+        let mut witness = random_nonzero_below(rng, &bound) + T::one();
+        let probable = miller_rabin(candidate, &witness);
+        witness.zeroize();
+        if !probable {
+            return Ok(false);
+        }
+        report(progress, KeyGenProgress::Round { index, round })?;
+    }
+
+    if !strong_lucas(candidate) {
+        return Ok(false);
+    }
+    report(
+        progress,
+        KeyGenProgress::Round {
+            index,
+            round: rounds,
+        },
+    )?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    fn is_prime(n: u64) -> bool {
+        n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0)
+    }
+
+    fn no_progress(_: KeyGenProgress) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    #[test]
+    fn test_is_probable_prime() {
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        for n in (7..5000u64).step_by(2) {
+            let result = is_probable_prime(&mut rng, &n, 20, 0, &mut no_progress).unwrap();
+            assert_eq!(result, is_prime(n), "n = {}", n);
+        }
+        // Carmichael numbers and a strong pseudoprime to bases 2, 3, 5 and 7
+        for n in [561u64, 1105, 1729, 2465, 2821, 6601, 8911, 3_215_031_751] {
+            assert!(!is_probable_prime(&mut rng, &n, 20, 0, &mut no_progress).unwrap());
+        }
+        let largest = 18_446_744_073_709_551_557_u64;
+        assert!(is_probable_prime(&mut rng, &largest, 20, 0, &mut no_progress).unwrap());
+    }
+
+    #[test]
+    fn test_generate_prime() {
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        for bits in 3..=32 {
+            let prime: u64 = generate_prime(&mut rng, bits, 0, &mut no_progress).unwrap();
+            assert_eq!(prime.bits(), bits);
+            assert_eq!(prime >> (bits - 2), 3);
+            assert!(is_prime(prime), "{} is not prime", prime);
+        }
+    }
+
+    #[test]
+    fn test_progress_and_cancel() {
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        let mut found = 0;
+        let mut count_found = |event: KeyGenProgress| {
+            if let KeyGenProgress::PrimeFound { .. } = event {
+                found += 1;
+            }
+            ControlFlow::Continue(())
+        };
+        generate_multi_prime_key_with_exp::<_, _, _, 2>(
+            &mut rng,
+            2,
+            64,
+            65537u64,
+            &mut count_found,
+        )
+        .unwrap();
+        assert!(found >= 2);
+
+        let mut rounds = 0;
+        let mut cancel = |event: KeyGenProgress| match event {
+            KeyGenProgress::Round { .. } if rounds == 3 => ControlFlow::Break(()),
+            KeyGenProgress::Round { .. } => {
+                rounds += 1;
+                ControlFlow::Continue(())
+            }
+            _ => ControlFlow::Continue(()),
+        };
+        let result =
+            generate_multi_prime_key_with_exp::<_, _, _, 2>(&mut rng, 2, 64, 65537u64, &mut cancel);
+        assert!(matches!(result, Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn test_rejects_invalid_sizes() {
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        let result = generate_multi_prime_key_with_exp::<_, _, _, 2>(
+            &mut rng,
+            2,
+            128,
+            65537u64,
+            &mut no_progress,
+        );
+        assert!(matches!(result, Err(Error::ModulusTooLarge)));
+        let result = generate_multi_prime_key_with_exp::<_, _, _, 2>(
+            &mut rng,
+            1,
+            64,
+            65537u64,
+            &mut no_progress,
+        );
+        assert!(matches!(result, Err(Error::NprimesTooSmall)));
+        let result = generate_multi_prime_key_with_exp::<_, _, _, 2>(
+            &mut rng,
+            3,
+            64,
+            65537u64,
+            &mut no_progress,
+        );
+        assert!(matches!(result, Err(Error::InvalidArguments)));
+        let result =
+            generate_multi_prime_key_with_exp::<_, _, _, 2>(&mut rng, 2, 8, 3u64, &mut no_progress);
+        assert!(matches!(result, Err(Error::TooFewPrimes)));
+    }
+}
@@ -1,10 +1,16 @@
 //! Mask generation function common to both PSS and OAEP padding
 
 use digest::{Digest, DynDigest, FixedOutputReset};
+use zeroize::Zeroize;
+
+// Maxiumum supported length of digests
+// This gets temporarily stack-allocated
+const MAX_DIGEST_LEN: usize = 64;
 
 /// Mask generation function.
 ///
 /// Panics if out is larger than 2**32. This is in accordance with RFC 8017 - PKCS #1 B.2.1
+/// Also panics if the digest output is longer than 64 bytes.
 pub(crate) fn mgf1_xor(out: &mut [u8], digest: &mut dyn DynDigest, seed: &[u8]) {
     let mut counter = [0u8; 4];
     let mut i = 0;
@@ -12,7 +18,28 @@ pub(crate) fn mgf1_xor(out: &mut [u8], digest: &mut dyn DynDigest, seed: &[u8])
     const MAX_LEN: u64 = u32::MAX as u64 + 1;
     assert!(out.len() as u64 <= MAX_LEN);
 
-    todo!()
+    let h_len = digest.output_size();
+    let mut digest_output = [0u8; MAX_DIGEST_LEN];
+    while i < out.len() {
+        digest.update(seed);
+        digest.update(&counter);
+
+        digest
+            .finalize_into_reset(&mut digest_output[..h_len])
+            .expect("digest output fits MAX_DIGEST_LEN");
+        let mut j = 0;
+        loop {
+            if j >= h_len || i >= out.len() {
+                break;
+            }
+
+            out[i] ^= digest_output[j];
+            j += 1;
+            i += 1;
+        }
+        inc_counter(&mut counter);
+    }
+    digest_output.zeroize();
 }
 
 /// Mask generation function.
@@ -1,3 +1,7 @@
 mod add;
+pub(crate) mod ct;
 pub(crate) mod exp;
-mod mul;
+pub(crate) mod inverse;
+pub(crate) mod montgomery;
+pub(crate) mod mul;
+pub(crate) mod wide;
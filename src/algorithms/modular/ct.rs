@@ -0,0 +1,432 @@
+//! Constant-time modular arithmetic for private key operations.
+//!
+//! Everything in this module avoids branches and early exits that depend on
+//! the values being operated on. Only the modulus, and values derived from its
+//! bit length, are treated as public. Selections are done with all-ones/all-zero
+//! masks in the style of [`subtle::ConditionallySelectable`], and carries and
+//! borrows are recovered with bitwise formulas instead of comparisons.
+//!
+//! Exponentiation uses a Montgomery ladder with an iteration count fixed by the
+//! bit length of the modulus, so neither the exponent's bit length nor its
+//! Hamming weight affects timing. Any operation involving private key material
+//! must go through this module rather than [`super::exp::mod_exp`].
+
+use num_traits::{NumCast, ToPrimitive};
+use subtle::Choice;
+
+use super::montgomery::{type_bits, Montgomery};
+use crate::traits::UnsignedModularInt;
+
+/// Converts a 0/1 valued `T` into a [`Choice`].
+#[inline]
+fn bit_to_choice<T>(bit: T) -> Choice
+where
+    T: UnsignedModularInt,
+{
+    Choice::from(bit.to_u8().unwrap_or(0) & 1)
+}
+
+/// Converts a [`Choice`] into a 0/1 valued `T`.
+#[inline]
+fn choice_to_bit<T>(choice: Choice) -> T
+where
+    T: UnsignedModularInt,
+{
+    <T as NumCast>::from(choice.unwrap_u8()).unwrap_or_else(T::zero) & T::one()
+}
+
+/// Expands a 0/1 valued `T` into an all-zero or all-ones mask.
+#[inline]
+fn bit_mask<T>(bit: T) -> T
+where
+    T: UnsignedModularInt,
+{
+    T::zero().wrapping_sub(&bit)
+}
+
+/// Returns the most significant bit of `x` as a 0/1 valued `T`.
+#[inline]
+fn top_bit<T>(x: T) -> T
+where
+    T: UnsignedModularInt,
+{
+    (x >> (type_bits::<T>() - 1)) & T::one()
+}
+
+/// Carry out of `a + b`, given their wrapped sum.
+#[inline]
+fn carry_bit<T>(a: T, b: T, sum: T) -> T
+where
+    T: UnsignedModularInt,
+{
+    top_bit((a & b) | ((a | b) & !sum))
+}
+
+/// Borrow out of `a - b`, given their wrapped difference. This is one exactly
+/// when `a < b`.
+#[inline]
+fn borrow_bit<T>(a: T, b: T, diff: T) -> T
+where
+    T: UnsignedModularInt,
+{
+    top_bit((!a & b) | (!(a ^ b) & diff))
+}
+
+/// Selects `b` if the 0/1 valued `bit` is set, `a` otherwise.
+#[inline]
+fn select_bit<T>(a: T, b: T, bit: T) -> T
+where
+    T: UnsignedModularInt,
+{
+    a ^ (bit_mask(bit) & (a ^ b))
+}
+
+/// Selects `b` if `choice` is set, `a` otherwise.
+#[inline]
+pub(crate) fn ct_select<T>(a: T, b: T, choice: Choice) -> T
+where
+    T: UnsignedModularInt,
+{
+    select_bit(a, b, choice_to_bit(choice))
+}
+
+/// Swaps `a` and `b` if `choice` is set.
+#[inline]
+pub(crate) fn ct_swap<T>(a: &mut T, b: &mut T, choice: Choice)
+where
+    T: UnsignedModularInt,
+{
+    let t = bit_mask::<T>(choice_to_bit(choice)) & (*a ^ *b);
+    *a = *a ^ t;
+    *b = *b ^ t;
+}
+
+/// Returns whether `a == b`.
+#[inline]
+pub(crate) fn ct_eq<T>(a: T, b: T) -> Choice
+where
+    T: UnsignedModularInt,
+{
+    let x = a ^ b;
+    // x | -x has its top bit set exactly when x is non-zero
+    !bit_to_choice(top_bit(x | T::zero().wrapping_sub(&x)))
+}
+
+/// Returns whether `a < b`.
+#[inline]
+pub(crate) fn ct_lt<T>(a: T, b: T) -> Choice
+where
+    T: UnsignedModularInt,
+{
+    bit_to_choice(borrow_bit(a, b, a.wrapping_sub(&b)))
+}
+
+/// Computes `a + b mod m` for `a, b < m`.
+#[inline]
+pub(crate) fn ct_add_mod<T>(a: T, b: T, m: T) -> T
+where
+    T: UnsignedModularInt,
+{
+    let sum = a.wrapping_add(&b);
+    let carry = carry_bit(a, b, sum);
+    let reduced = sum.wrapping_sub(&m);
+    let borrow = borrow_bit(sum, m, reduced);
+    // Keep the unreduced sum only if it did not overflow and is below m.
+    select_bit(reduced, sum, borrow & !carry & T::one())
+}
+
+/// Computes `a - b mod m` for `a, b < m`.
+#[inline]
+pub(crate) fn ct_sub_mod<T>(a: T, b: T, m: T) -> T
+where
+    T: UnsignedModularInt,
+{
+    let diff = a.wrapping_sub(&b);
+    let borrow = borrow_bit(a, b, diff);
+    select_bit(diff, diff.wrapping_add(&m), borrow)
+}
+
+/// Reduces an arbitrary `a` modulo `m ≥ 1`, processing every bit of `T`.
+///
+/// Unlike [`CtMontgomery`], this works for even moduli such as `p - 1`, and
+/// `m` may be secret.
+pub(crate) fn ct_reduce<T>(a: T, m: T) -> T
+where
+    T: UnsignedModularInt,
+{
+    let mut r = T::zero();
+    for i in (0..type_bits::<T>()).rev() {
+        // r < m, so 2r + bit < 2m needs at most one conditional subtraction
+        let doubled = r.wrapping_add(&r);
+        let carry = carry_bit(r, r, doubled);
+        let shifted = doubled | ((a >> i) & T::one());
+        let reduced = shifted.wrapping_sub(&m);
+        let borrow = borrow_bit(shifted, m, reduced);
+        r = select_bit(reduced, shifted, borrow & !carry & T::one());
+    }
+    r
+}
+
+/// Computes `a^-1 mod p` for a secret odd prime `p` in constant time, as
+/// `a^(p-2) mod p`.
+///
+/// Returns `None` if `p` is not an odd prime larger than 1, or `a` is a
+/// multiple of it.
+pub(crate) fn ct_invert_prime<T>(a: T, p: T) -> Option<T>
+where
+    T: UnsignedModularInt,
+{
+    let two = T::one() + T::one();
+    CtMontgomery::new(p)?.invert(a, p - two)
+}
+
+/// Constant-time Montgomery arithmetic modulo an odd, public modulus.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CtMontgomery<T>
+where
+    T: UnsignedModularInt,
+{
+    params: Montgomery<T>,
+}
+
+impl<T> CtMontgomery<T>
+where
+    T: UnsignedModularInt,
+{
+    /// Prepares constant-time Montgomery parameters for `modulus`.
+    ///
+    /// Returns `None` if the modulus is even or smaller than 3. The modulus
+    /// itself is treated as public.
+    pub(crate) fn new(modulus: T) -> Option<Self> {
+        Montgomery::new(modulus).map(|params| Self { params })
+    }
+
+    /// Returns the modulus.
+    pub(crate) fn modulus(&self) -> T {
+        self.params.modulus()
+    }
+
+    /// Montgomery product: computes `a * b * R^-1 mod m` for `a, b < m`.
+    pub(crate) fn mul(&self, a: T, b: T) -> T {
+        let m = self.params.modulus();
+        let top_shift = type_bits::<T>() - 1;
+        let mut a = a;
+        let mut t = T::zero();
+
+        for _ in 0..self.params.bits() {
+            let addend = b & bit_mask(a & T::one());
+            let sum = t.wrapping_add(&addend);
+            let c1 = carry_bit(t, addend, sum);
+
+            let addend = m & bit_mask(sum & T::one());
+            let sum2 = sum.wrapping_add(&addend);
+            let c2 = carry_bit(sum, addend, sum2);
+
+            // Halve the three word (c1 + c2, sum2) value, then bring it below m.
+            t = (sum2 >> 1) | ((c1 ^ c2) << top_shift);
+            let reduced = t.wrapping_sub(&m);
+            let borrow = borrow_bit(t, m, reduced);
+            t = select_bit(reduced, t, borrow & !(c1 & c2) & T::one());
+
+            a = a >> 1;
+        }
+        t
+    }
+
+    /// Montgomery squaring: computes `a * a * R^-1 mod m` for `a < m`.
+    #[inline]
+    pub(crate) fn square(&self, a: T) -> T {
+        self.mul(a, a)
+    }
+
+    /// Reduces an arbitrary `a` modulo `m`, processing every bit of `T`.
+    pub(crate) fn reduce(&self, a: T) -> T {
+        ct_reduce(a, self.params.modulus())
+    }
+
+    /// Converts `a < m` into Montgomery form, `a * R mod m`.
+    #[inline]
+    pub(crate) fn montgomery_form(&self, a: T) -> T {
+        self.mul(a, self.params.r2())
+    }
+
+    /// Converts `a` out of Montgomery form, `a * R^-1 mod m`.
+    #[inline]
+    pub(crate) fn canonical_form(&self, a: T) -> T {
+        self.mul(a, T::one())
+    }
+
+    /// Computes `a * b mod m` for `a, b < m`.
+    #[inline]
+    pub(crate) fn mul_mod(&self, a: T, b: T) -> T {
+        // (aR) * b * R^-1 = ab
+        self.mul(self.montgomery_form(a), b)
+    }
+
+    /// Computes `base^exponent mod m` with a Montgomery ladder.
+    ///
+    /// The ladder always runs for the bit length of the modulus, performing one
+    /// multiplication and one squaring per step. `exponent` must therefore be
+    /// smaller than `2^k` where `k` is the bit length of the modulus, which holds
+    /// for any private exponent reduced modulo `m - 1` or `λ(m)`.
+    pub(crate) fn pow(&self, base: T, exponent: T) -> T {
+        let mut r0 = self.params.one();
+        let mut r1 = self.montgomery_form(self.reduce(base));
+        for i in (0..self.params.bits()).rev() {
+            let bit = bit_to_choice((exponent >> i) & T::one());
+            ct_swap(&mut r0, &mut r1, bit);
+            r1 = self.mul(r0, r1);
+            r0 = self.square(r0);
+            ct_swap(&mut r0, &mut r1, bit);
+        }
+        self.canonical_form(r0)
+    }
+
+    /// Computes `a^-1 mod m` for a secret `a` with the ladder, given the secret
+    /// exponent `φ(m) - 1`, as `a^(φ(m) - 1) mod m`.
+    ///
+    /// Returns `None` if `a` is not coprime to `m`. Only that outcome depends on
+    /// `a`, every input takes the same time.
+    pub(crate) fn invert(&self, a: T, phi_minus_one: T) -> Option<T> {
+        let inverse = self.pow(a, phi_minus_one);
+        let check = self.mul_mod(self.reduce(a), inverse);
+        bool::from(ct_eq(check, T::one())).then_some(inverse)
+    }
+
+    /// Computes `base^exponent mod m` for a secret `base` and a public `exponent`.
+    ///
+    /// Each multiplication is constant time, but the square-and-multiply schedule
+    /// follows the bits of `exponent`. This is meant for applying the public
+    /// exponent to secret values, e.g. blinding factors or recovered plaintexts.
+    pub(crate) fn pow_public(&self, base: T, exponent: T) -> T {
+        let base = self.montgomery_form(self.reduce(base));
+        let mut result = self.params.one();
+        for i in (0..exponent.bits()).rev() {
+            result = self.square(result);
+            if (exponent >> i) & T::one() == T::one() {
+                result = self.mul(result, base);
+            }
+        }
+        self.canonical_form(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::modular::exp::mod_exp;
+
+    #[test]
+    fn test_select_and_swap() {
+        assert_eq!(ct_select(3u8, 7u8, Choice::from(0)), 3);
+        assert_eq!(ct_select(3u8, 7u8, Choice::from(1)), 7);
+
+        let (mut a, mut b) = (0xAAAA_u16, 0x5555_u16);
+        ct_swap(&mut a, &mut b, Choice::from(0));
+        assert_eq!((a, b), (0xAAAA, 0x5555));
+        ct_swap(&mut a, &mut b, Choice::from(1));
+        assert_eq!((a, b), (0x5555, 0xAAAA));
+    }
+
+    #[test]
+    fn test_eq_lt() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                assert_eq!(bool::from(ct_eq(a, b)), a == b);
+                assert_eq!(bool::from(ct_lt(a, b)), a < b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_add_sub_mod() {
+        for m in 1..=255u8 {
+            for a in 0..m {
+                for b in (0..m).step_by(3) {
+                    let expected = ((a as u16 + b as u16) % m as u16) as u8;
+                    assert_eq!(ct_add_mod(a, b, m), expected);
+                    let expected = ((a as i16 - b as i16).rem_euclid(m as i16)) as u8;
+                    assert_eq!(ct_sub_mod(a, b, m), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reduce() {
+        let mont = CtMontgomery::new(251u8).unwrap();
+        for a in 0..=255u8 {
+            assert_eq!(mont.reduce(a), a % 251);
+        }
+        let m = 18_446_744_073_709_551_557_u64;
+        let mont = CtMontgomery::new(m).unwrap();
+        assert_eq!(mont.reduce(u64::MAX), u64::MAX % m);
+
+        // Even moduli, as for d mod (p-1)
+        for m in 1..=255u8 {
+            for a in 0..=255u8 {
+                assert_eq!(ct_reduce(a, m), a % m);
+            }
+        }
+        assert_eq!(ct_reduce(u64::MAX, m - 1), u64::MAX % (m - 1));
+    }
+
+    #[test]
+    fn test_mul_mod_matches_reference() {
+        for m in (3..=255u8).step_by(2) {
+            let mont = CtMontgomery::new(m).unwrap();
+            for a in (0..m).step_by(3) {
+                for b in (0..m).step_by(7) {
+                    let expected = ((a as u16 * b as u16) % m as u16) as u8;
+                    assert_eq!(mont.mul_mod(a, b), expected);
+                }
+            }
+        }
+
+        let m = 18_446_744_073_709_551_557_u64;
+        let mont = CtMontgomery::new(m).unwrap();
+        for &(a, b) in &[(m - 1, m - 1), (m - 2, 3), (0xDEAD_BEEF_CAFE_BABE, m - 5)] {
+            let expected = ((a as u128 * b as u128) % m as u128) as u64;
+            assert_eq!(mont.mul_mod(a, b), expected);
+        }
+    }
+
+    #[test]
+    fn test_pow_matches_mod_exp() {
+        for m in (3..=255u8).step_by(2) {
+            let mont = CtMontgomery::new(m).unwrap();
+            for b in (0..=255u8).step_by(11) {
+                for e in (0..m).step_by(5) {
+                    assert_eq!(mont.pow(b, e), mod_exp(b, e, m));
+                }
+            }
+        }
+
+        let m = 1_000_000_007_u64;
+        let mont = CtMontgomery::new(m).unwrap();
+        assert_eq!(mont.pow(123_456_789, 987_654_321), 652_541_198);
+        assert_eq!(mont.pow_public(123_456_789, 987_654_321), 652_541_198);
+
+        let m = 18_446_744_073_709_551_557_u64;
+        let mont = CtMontgomery::new(m).unwrap();
+        assert_eq!(mont.pow(u64::MAX, m - 2), mod_exp(u64::MAX, m - 2, m));
+    }
+
+    #[test]
+    fn test_invert() {
+        for p in [3u8, 5, 7, 11, 13, 251] {
+            for a in 0..=255u8 {
+                let expected = (1..p).find(|x| (*x as u16 * (a % p) as u16) % p as u16 == 1);
+                assert_eq!(ct_invert_prime(a, p), expected, "a = {}, p = {}", a, p);
+            }
+        }
+        assert_eq!(ct_invert_prime(3u8, 4), None);
+
+        // n = 11 * 13, φ(n) = 120
+        let mont = CtMontgomery::new(143u8).unwrap();
+        for a in 0..143u8 {
+            let expected = (1..143).find(|x| (*x as u16 * a as u16) % 143 == 1);
+            assert_eq!(mont.invert(a, 119), expected, "a = {}", a);
+        }
+    }
+}
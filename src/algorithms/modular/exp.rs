@@ -1,8 +1,26 @@
 use crate::traits::UnsignedModularInt;
 
+use super::montgomery::Montgomery;
 use super::mul::mod_mul;
 
-pub fn mod_exp<T>(mut base: T, exponent: T, modulus: T) -> T
+/// Computes `base^exponent mod modulus`.
+///
+/// Odd moduli, which covers every RSA modulus and prime, are handled in
+/// Montgomery form. Even moduli fall back to plain square-and-multiply.
+///
+/// This is not constant time and must only be used with public exponents.
+pub fn mod_exp<T>(base: T, exponent: T, modulus: T) -> T
+where
+    T: UnsignedModularInt,
+{
+    match Montgomery::new(modulus) {
+        Some(mont) => mont.pow(base, exponent),
+        None => mod_exp_binary(base, exponent, modulus),
+    }
+}
+
+/// Square-and-multiply exponentiation built on double-and-add multiplication.
+fn mod_exp_binary<T>(mut base: T, exponent: T, modulus: T) -> T
 where
     T: UnsignedModularInt,
 {
@@ -129,6 +147,23 @@ mod tests {
         assert_eq!(mod_exp(7_u8, 13_u8, 19_u8), 7_u8); // 7^13 % 19 = 7
     }
 
+    #[test]
+    fn test_even_modulus() {
+        assert_eq!(mod_exp(3_u64, 5_u64, 16_u64), 3_u64); // 243 % 16 = 3
+        assert_eq!(mod_exp(7_u8, 3_u8, 100_u8), 43_u8); // 343 % 100 = 43
+    }
+
+    #[test]
+    fn test_matches_binary() {
+        for m in 2..=255u8 {
+            for b in (0..=255u8).step_by(17) {
+                for e in (0..=255u8).step_by(13) {
+                    assert_eq!(mod_exp(b, e, m), mod_exp_binary(b, e, m));
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_coprime_values() {
         assert_eq!(
@@ -0,0 +1,225 @@
+//! Modular inverse and greatest common divisor for unsigned integers.
+//!
+//! Everything here is division free: the binary algorithms only need shifts,
+//! additions and subtractions, which keeps them fast on wide fixed-size
+//! integers, and the Bézout coefficients stay within `[0, m)` so no signed
+//! arithmetic is required.
+//!
+//! The loop counts and branches depend on the inputs, so these must only see
+//! public values, or secrets during key setup such as `e^-1 mod λ(n)`.
+//! Inverses within private key operations, e.g. of blinding factors or modulo
+//! the primes, use [`super::ct::CtMontgomery::invert`] instead.
+
+use super::ct::ct_sub_mod;
+use super::montgomery::type_bits;
+use crate::traits::UnsignedModularInt;
+
+/// Computes the multiplicative inverse of `a` modulo `m`.
+///
+/// Returns `None` if `a` and `m` are not coprime, or if `m < 2`. `a` does not
+/// need to be reduced modulo `m`.
+///
+/// Odd moduli are handled by the binary extended Euclidean algorithm. An even
+/// modulus `m = 2^t·m'` is split into its odd part `m'` and `2^t`, the inverse
+/// is computed modulo each of them and then recombined with the CRT.
+pub(crate) fn mod_inverse<T>(a: T, m: T) -> Option<T>
+where
+    T: UnsignedModularInt,
+{
+    if m <= T::one() {
+        return None;
+    }
+    if !m.is_even() {
+        return mod_inverse_odd(a, m);
+    }
+    if a.is_even() {
+        return None;
+    }
+
+    let t = m.trailing_zeros() as usize;
+    let odd = m >> t;
+    let low = inverse_pow2(a, t);
+    if odd.is_one() {
+        return Some(low);
+    }
+    let high = mod_inverse_odd(a, odd)?;
+
+    // x = high + odd·h with h = (low - high)·odd^-1 mod 2^t, so that
+    // x ≡ high mod odd and x ≡ low mod 2^t. As h < 2^t, x < m.
+    let mask = low_mask::<T>(t);
+    let h = mul_pow2(low.wrapping_sub(&high) & mask, inverse_pow2(odd, t), t);
+    Some(high + odd * h)
+}
+
+/// Binary extended Euclidean algorithm for an odd modulus `m > 1`.
+fn mod_inverse_odd<T>(a: T, m: T) -> Option<T>
+where
+    T: UnsignedModularInt,
+{
+    // Invariants: x1·a ≡ u and x2·a ≡ v (mod m). `u` starts out unreduced,
+    // the subtractions below reduce it along with the gcd.
+    let mut u = a;
+    let mut v = m;
+    let mut x1 = T::one();
+    let mut x2 = T::zero();
+
+    if u.is_zero() {
+        return None;
+    }
+    while !u.is_one() && !v.is_one() {
+        while u.is_even() {
+            u = u >> 1;
+            x1 = half_mod(x1, &m);
+        }
+        while v.is_even() {
+            v = v >> 1;
+            x2 = half_mod(x2, &m);
+        }
+        if u >= v {
+            u = u - v;
+            x1 = ct_sub_mod(x1, x2, m);
+            if u.is_zero() {
+                // gcd(a, m) = v > 1
+                return None;
+            }
+        } else {
+            v = v - u;
+            x2 = ct_sub_mod(x2, x1, m);
+        }
+    }
+
+    Some(if u.is_one() { x1 } else { x2 })
+}
+
+/// Computes `a / 2 mod m` for `a < m` and odd `m`.
+pub(crate) fn half_mod<T>(a: T, m: &T) -> T
+where
+    T: UnsignedModularInt,
+{
+    if a.is_even() {
+        a >> 1
+    } else {
+        // (a + m) / 2, without overflowing
+        (a >> 1) + (*m >> 1) + T::one()
+    }
+}
+
+/// Mask of the `t` lowest bits.
+fn low_mask<T>(t: usize) -> T
+where
+    T: UnsignedModularInt,
+{
+    T::max_value() >> (type_bits::<T>() - t)
+}
+
+/// Computes `a·b mod 2^t`.
+fn mul_pow2<T>(a: T, b: T, t: usize) -> T
+where
+    T: UnsignedModularInt,
+{
+    let mut result = T::zero();
+    for i in 0..t {
+        if (b >> i) & T::one() == T::one() {
+            result = result.wrapping_add(&(a << i));
+        }
+    }
+    result & low_mask(t)
+}
+
+/// Computes `a^-1 mod 2^t` for odd `a`, by Newton iteration.
+fn inverse_pow2<T>(a: T, t: usize) -> T
+where
+    T: UnsignedModularInt,
+{
+    let two = T::one() + T::one();
+    let mask = low_mask::<T>(t);
+    // Any odd a is its own inverse modulo 2, each step doubles the precision.
+    let mut x = T::one();
+    let mut precision = 1;
+    while precision < t {
+        let ax = mul_pow2(a, x, t);
+        x = mul_pow2(x, two.wrapping_sub(&ax) & mask, t);
+        precision *= 2;
+    }
+    x & mask
+}
+
+/// Greatest common divisor, using the binary GCD algorithm.
+pub(crate) fn gcd<T>(a: T, b: T) -> T
+where
+    T: UnsignedModularInt,
+{
+    if a.is_zero() {
+        return b;
+    }
+    if b.is_zero() {
+        return a;
+    }
+    let shift = (a | b).trailing_zeros() as usize;
+    let mut a = a >> a.trailing_zeros() as usize;
+    let mut b = b;
+    loop {
+        b = b >> b.trailing_zeros() as usize;
+        if a > b {
+            core::mem::swap(&mut a, &mut b);
+        }
+        b = b - a;
+        if b.is_zero() {
+            return a << shift;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mod_inverse_small() {
+        for m in 2..=255u8 {
+            for a in 0..=255u8 {
+                let expected = (1..m).find(|x| (*x as u16 * (a % m) as u16) % m as u16 == 1);
+                assert_eq!(mod_inverse(a, m), expected, "a = {}, m = {}", a, m);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse_u64() {
+        let m = 18_446_744_073_709_551_557_u64;
+        let a = 0xDEAD_BEEF_CAFE_BABE_u64;
+        let inv = mod_inverse(a, m).unwrap();
+        assert_eq!((a as u128 * inv as u128) % m as u128, 1);
+
+        // even modulus, as used for the private exponent
+        let phi = 18_446_743_979_220_271_104_u64;
+        let inv = mod_inverse(65537, phi).unwrap();
+        assert_eq!((65537 * inv as u128) % phi as u128, 1);
+
+        // power of two modulus
+        let inv = mod_inverse(a + 1, 1 << 63).unwrap();
+        assert_eq!((a + 1).wrapping_mul(inv) & ((1 << 63) - 1), 1);
+
+        assert_eq!(mod_inverse(u64::MAX, u64::MAX - 1), Some(1));
+        assert_eq!(mod_inverse(6u64, 9), None);
+        assert_eq!(mod_inverse(6u64, 10), None);
+        assert_eq!(mod_inverse(5u64, 1), None);
+        assert_eq!(mod_inverse(5u64, 0), None);
+    }
+
+    #[test]
+    fn test_gcd() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                let expected = (1..=255u8)
+                    .rev()
+                    .find(|d| a % d == 0 && b % d == 0)
+                    .filter(|_| a != 0 || b != 0)
+                    .unwrap_or(0);
+                assert_eq!(gcd(a, b), expected, "gcd({}, {})", a, b);
+            }
+        }
+        assert_eq!(gcd(u64::MAX, u64::MAX - 1), 1);
+        assert_eq!(gcd(1u64 << 63, 3 << 40), 1 << 40);
+    }
+}
@@ -0,0 +1,257 @@
+//! Montgomery form arithmetic for odd moduli.
+//!
+//! Products are reduced with bit-serial (radix-2) Montgomery reduction, which
+//! only needs additions, shifts and comparisons on `T`. No double-width product
+//! and no `%` reduction is performed inside the multiplication loop, which makes
+//! this considerably faster than double-and-add on wide fixed-size integers.
+
+use crate::traits::UnsignedModularInt;
+
+/// Adds two values, returning the wrapped sum and whether it overflowed.
+#[inline]
+pub(crate) fn overflowing_add<T>(a: T, b: T) -> (T, bool)
+where
+    T: UnsignedModularInt,
+{
+    let sum = a.wrapping_add(&b);
+    (sum, sum < a)
+}
+
+/// Width of `T` in bits.
+#[inline]
+pub(crate) fn type_bits<T>() -> usize
+where
+    T: UnsignedModularInt,
+{
+    T::zero().count_zeros() as usize
+}
+
+/// Precomputed parameters for arithmetic in Montgomery form modulo an odd `m`,
+/// using `R = 2^k` where `k` is the bit length of `m`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Montgomery<T>
+where
+    T: UnsignedModularInt,
+{
+    /// Odd modulus
+    modulus: T,
+    /// Bit length of the modulus, `k` in `R = 2^k`
+    bits: usize,
+    /// R mod m, i.e. one in Montgomery form
+    one: T,
+    /// R^2 mod m, used to convert into Montgomery form
+    r2: T,
+}
+
+impl<T> Montgomery<T>
+where
+    T: UnsignedModularInt,
+{
+    /// Prepares Montgomery parameters for `modulus`.
+    ///
+    /// Returns `None` if the modulus is even or smaller than 3, as Montgomery
+    /// reduction is not defined for those.
+    pub(crate) fn new(modulus: T) -> Option<Self> {
+        if modulus.is_even() || modulus <= T::one() {
+            return None;
+        }
+        let bits = modulus.bits();
+        let mut one = T::one();
+        for _ in 0..bits {
+            one = Self::double(one, modulus);
+        }
+        let mut r2 = one;
+        for _ in 0..bits {
+            r2 = Self::double(r2, modulus);
+        }
+        Some(Self {
+            modulus,
+            bits,
+            one,
+            r2,
+        })
+    }
+
+    /// Returns the modulus.
+    pub(crate) fn modulus(&self) -> T {
+        self.modulus
+    }
+
+    /// Returns the bit length of the modulus.
+    pub(crate) fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Returns one in Montgomery form.
+    pub(crate) fn one(&self) -> T {
+        self.one
+    }
+
+    /// Returns `R^2 mod m`.
+    pub(crate) fn r2(&self) -> T {
+        self.r2
+    }
+
+    /// Computes `2a mod m` for `a < m`.
+    #[inline]
+    fn double(a: T, modulus: T) -> T {
+        let (sum, carry) = overflowing_add(a, a);
+        if carry || sum >= modulus {
+            sum.wrapping_sub(&modulus)
+        } else {
+            sum
+        }
+    }
+
+    /// Montgomery product (REDC): computes `a * b * R^-1 mod m` for `a, b < m`.
+    pub(crate) fn mul(&self, a: T, b: T) -> T {
+        let m = self.modulus;
+        let top_shift = type_bits::<T>() - 1;
+        let mut a = a;
+        let mut t = T::zero();
+
+        for _ in 0..self.bits {
+            // t < m holds here, so t + b + m < 3m and at most two carries
+            // out of the top word can occur.
+            let mut high = 0u8;
+            if a & T::one() == T::one() {
+                let (sum, carry) = overflowing_add(t, b);
+                t = sum;
+                high += carry as u8;
+            }
+            if t & T::one() == T::one() {
+                let (sum, carry) = overflowing_add(t, m);
+                t = sum;
+                high += carry as u8;
+            }
+            // Halve the (high, t) double word, which is now even.
+            t = t >> 1;
+            if high & 1 == 1 {
+                t = t | (T::one() << top_shift);
+            }
+            // The halved value is below 3m/2, one subtraction brings it below m.
+            if high >> 1 == 1 || t >= m {
+                t = t.wrapping_sub(&m);
+            }
+            a = a >> 1;
+        }
+        t
+    }
+
+    /// Montgomery squaring: computes `a * a * R^-1 mod m` for `a < m`.
+    #[inline]
+    pub(crate) fn square(&self, a: T) -> T {
+        self.mul(a, a)
+    }
+
+    /// Converts `a` into Montgomery form, `a * R mod m`.
+    pub(crate) fn montgomery_form(&self, a: T) -> T {
+        let a = if a >= self.modulus {
+            a % self.modulus
+        } else {
+            a
+        };
+        self.mul(a, self.r2)
+    }
+
+    /// Converts `a` out of Montgomery form, `a * R^-1 mod m`.
+    pub(crate) fn canonical_form(&self, a: T) -> T {
+        self.mul(a, T::one())
+    }
+
+    /// Computes `base^exponent mod m` with left-to-right square-and-multiply
+    /// in Montgomery form.
+    ///
+    /// This is not constant time and must only be used with public exponents.
+    pub(crate) fn pow(&self, base: T, exponent: T) -> T {
+        let base = self.montgomery_form(base);
+        let mut result = self.one;
+        for i in (0..exponent.bits()).rev() {
+            result = self.square(result);
+            if (exponent >> i) & T::one() == T::one() {
+                result = self.mul(result, base);
+            }
+        }
+        self.canonical_form(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference_mul_mod(a: u64, b: u64, m: u64) -> u64 {
+        ((a as u128 * b as u128) % m as u128) as u64
+    }
+
+    #[test]
+    fn test_rejects_even_modulus() {
+        assert!(Montgomery::new(0u8).is_none());
+        assert!(Montgomery::new(1u8).is_none());
+        assert!(Montgomery::new(20u8).is_none());
+        assert!(Montgomery::new(19u8).is_some());
+    }
+
+    #[test]
+    fn test_roundtrip_form() {
+        let mont = Montgomery::new(251u8).unwrap();
+        for a in 0..=255u8 {
+            let m = mont.montgomery_form(a);
+            assert!(m < 251);
+            assert_eq!(mont.canonical_form(m), a % 251);
+        }
+    }
+
+    #[test]
+    fn test_mul_matches_reference_u8() {
+        for m in (3..=255u8).step_by(2) {
+            let mont = Montgomery::new(m).unwrap();
+            for a in (0..m).step_by(7) {
+                for b in (0..m).step_by(5) {
+                    let prod = mont.mul(mont.montgomery_form(a), mont.montgomery_form(b));
+                    assert_eq!(
+                        mont.canonical_form(prod) as u64,
+                        reference_mul_mod(a as u64, b as u64, m as u64)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_full_width_modulus() {
+        // Largest 64-bit prime, exercises both carries out of the top word
+        let m = 18_446_744_073_709_551_557_u64;
+        let mont = Montgomery::new(m).unwrap();
+        let values = [0, 1, 2, m - 1, m - 2, u64::MAX / 3, 0xDEAD_BEEF_CAFE_BABE];
+        for &a in &values {
+            for &b in &values {
+                let prod = mont.mul(mont.montgomery_form(a), mont.montgomery_form(b));
+                assert_eq!(mont.canonical_form(prod), reference_mul_mod(a, b, m));
+            }
+        }
+    }
+
+    #[test]
+    fn test_square() {
+        let m = 1_000_000_007_u64;
+        let mont = Montgomery::new(m).unwrap();
+        let a = mont.montgomery_form(123_456_789);
+        assert_eq!(
+            mont.canonical_form(mont.square(a)),
+            reference_mul_mod(123_456_789, 123_456_789, m)
+        );
+    }
+
+    #[test]
+    fn test_pow() {
+        let mont = Montgomery::new(1_000_000_007_u64).unwrap();
+        assert_eq!(mont.pow(123_456_789, 987_654_321), 652_541_198);
+        assert_eq!(mont.pow(5, 0), 1);
+        assert_eq!(mont.pow(0, 5), 0);
+
+        let mont = Montgomery::new(u64::MAX).unwrap();
+        assert_eq!(mont.pow(u64::MAX, 2), 0);
+        assert_eq!(mont.pow(1 << 32, 2), 1);
+    }
+}
@@ -0,0 +1,225 @@
+//! Three-word unsigned integers built from `T`.
+//!
+//! A few computations on key components, like `d·e`, have intermediates wider
+//! than `T`. They are rare and not performance sensitive, so plain bit-serial
+//! arithmetic over three words is enough.
+
+use core::cmp::Ordering;
+
+use zeroize::Zeroize;
+
+use super::montgomery::{overflowing_add, type_bits};
+use crate::traits::UnsignedModularInt;
+
+const WORDS: usize = 3;
+
+/// Unsigned integer of three `T` words, least significant word first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Wide<T>
+where
+    T: UnsignedModularInt,
+{
+    words: [T; WORDS],
+}
+
+impl<T> Wide<T>
+where
+    T: UnsignedModularInt,
+{
+    /// Widens a single word.
+    pub(crate) fn from_word(a: T) -> Self {
+        let mut words = [T::zero(); WORDS];
+        words[0] = a;
+        Self { words }
+    }
+
+    /// Returns the value as a single word, if it fits.
+    pub(crate) fn to_word(self) -> Option<T> {
+        if self.words[1..].iter().all(|w| w.is_zero()) {
+            Some(self.words[0])
+        } else {
+            None
+        }
+    }
+
+    /// Number of significant bits.
+    fn bits(&self) -> usize {
+        match self.words.iter().rposition(|w| !w.is_zero()) {
+            Some(i) => i * type_bits::<T>() + self.words[i].bits(),
+            None => 0,
+        }
+    }
+
+    /// Returns bit `i`.
+    fn bit(&self, i: usize) -> bool {
+        let width = type_bits::<T>();
+        (self.words[i / width] >> (i % width)) & T::one() == T::one()
+    }
+
+    /// Shifts left by one bit, setting the lowest bit to `low`. The top bit is lost.
+    fn shl1(&self, low: bool) -> Self {
+        let top_shift = type_bits::<T>() - 1;
+        let mut carry = if low { T::one() } else { T::zero() };
+        let mut words = self.words;
+        for word in words.iter_mut() {
+            let next = *word >> top_shift;
+            *word = (*word << 1) | carry;
+            carry = next;
+        }
+        Self { words }
+    }
+
+    /// Adds `other`, returning `None` on overflow.
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        let mut words = self.words;
+        let mut carry = false;
+        for (word, other) in words.iter_mut().zip(other.words.iter()) {
+            let (sum, c1) = overflowing_add(*word, *other);
+            let (sum, c2) = overflowing_add(sum, if carry { T::one() } else { T::zero() });
+            *word = sum;
+            carry = c1 || c2;
+        }
+        if carry {
+            None
+        } else {
+            Some(Self { words })
+        }
+    }
+
+    /// Subtracts `other`, returning `None` if the result would be negative.
+    pub(crate) fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if *self < *other {
+            return None;
+        }
+        let mut words = self.words;
+        let mut borrow = false;
+        for (word, other) in words.iter_mut().zip(other.words.iter()) {
+            let (diff, b1) = (word.wrapping_sub(other), *word < *other);
+            let b2 = borrow && diff.is_zero();
+            *word = if borrow {
+                diff.wrapping_sub(&T::one())
+            } else {
+                diff
+            };
+            borrow = b1 || b2;
+        }
+        Some(Self { words })
+    }
+
+    /// Multiplies by the word `b`, returning `None` on overflow.
+    pub(crate) fn checked_mul(&self, b: T) -> Option<Self> {
+        let mut result = Self::from_word(T::zero());
+        for i in (0..b.bits()).rev() {
+            if result.bits() == WORDS * type_bits::<T>() {
+                return None;
+            }
+            result = result.shl1(false);
+            if (b >> i) & T::one() == T::one() {
+                result = result.checked_add(self)?;
+            }
+        }
+        Some(result)
+    }
+
+    /// Divides by the non-zero word `d`, returning the quotient and remainder.
+    pub(crate) fn div_rem(&self, d: T) -> (Self, T) {
+        let mut quotient = Self::from_word(T::zero());
+        let mut rem = T::zero();
+        for i in (0..self.bits()).rev() {
+            // rem < d, so 2·rem + 1 < 2d and one subtraction is enough.
+            let (doubled, carry) = overflowing_add(rem, rem);
+            let doubled = if self.bit(i) {
+                doubled | T::one()
+            } else {
+                doubled
+            };
+            let overflow = carry || doubled >= d;
+            rem = if overflow {
+                doubled.wrapping_sub(&d)
+            } else {
+                doubled
+            };
+            quotient = quotient.shl1(overflow);
+        }
+        (quotient, rem)
+    }
+}
+
+impl<T> PartialOrd for Wide<T>
+where
+    T: UnsignedModularInt,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Wide<T>
+where
+    T: UnsignedModularInt,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.words.iter().rev().cmp(other.words.iter().rev())
+    }
+}
+
+impl<T> Zeroize for Wide<T>
+where
+    T: UnsignedModularInt,
+{
+    fn zeroize(&mut self) {
+        self.words.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_u32(a: &Wide<u8>) -> u32 {
+        a.words
+            .iter()
+            .rev()
+            .fold(0u32, |acc, w| (acc << 8) | *w as u32)
+    }
+
+    fn from_u32(a: u32) -> Wide<u8> {
+        Wide {
+            words: [a as u8, (a >> 8) as u8, (a >> 16) as u8],
+        }
+    }
+
+    #[test]
+    fn test_mul_div() {
+        for a in (0..1u32 << 24).step_by(9973) {
+            for b in (0..=255u8).step_by(7) {
+                let product = from_u32(a).checked_mul(b);
+                if a * b as u32 >= 1 << 24 {
+                    assert!(product.is_none(), "{} * {}", a, b);
+                    continue;
+                }
+                let product = product.unwrap();
+                assert_eq!(to_u32(&product), a * b as u32);
+                if b != 0 {
+                    let (q, r) = from_u32(a).div_rem(b);
+                    assert_eq!(to_u32(&q), a / b as u32);
+                    assert_eq!(r as u32, a % b as u32);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sub_and_compare() {
+        let values = [0u32, 1, 255, 256, 0xFFFF, 0x1_0000, 0xAB_CDEF, 0xFF_FFFF];
+        for &a in &values {
+            for &b in &values {
+                let (wa, wb) = (from_u32(a), from_u32(b));
+                assert_eq!(wa.cmp(&wb), a.cmp(&b));
+                assert_eq!(wa.checked_sub(&wb).map(|d| to_u32(&d)), a.checked_sub(b));
+            }
+        }
+        assert_eq!(from_u32(0x1234).to_word(), None);
+        assert_eq!(from_u32(0x12).to_word(), Some(0x12));
+    }
+}
@@ -3,7 +3,7 @@
 use digest::{Digest, DynDigest, FixedOutputReset};
 use rand_core::CryptoRngCore;
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
-use zeroize::Zeroizing;
+use zeroize::{Zeroize, Zeroizing};
 
 use super::mgf::{mgf1_xor, mgf1_xor_digest};
 use crate::errors::{Error, Result};
@@ -35,7 +35,8 @@ fn encrypt_internal<'a, R: CryptoRngCore + ?Sized, MGF: FnMut(&mut [u8], &mut [u
     }
     let mut em = storage.get_mut(..k).ok_or(Error::OutputBufferTooSmall)?;
 
-    let (_, payload) = em.split_at_mut(1);
+    let (zero, payload) = em.split_at_mut(1);
+    zero[0] = 0;
     let (seed, db) = payload.split_at_mut(h_size);
     rng.fill_bytes(seed);
 
@@ -43,6 +44,9 @@ fn encrypt_internal<'a, R: CryptoRngCore + ?Sized, MGF: FnMut(&mut [u8], &mut [u
     let db_len = k - h_size - 1;
 
     db[0..h_size].copy_from_slice(p_hash);
+    for byte in &mut db[h_size..db_len - msg.len() - 1] {
+        *byte = 0;
+    }
     db[db_len - msg.len() - 1] = 1;
     db[db_len - msg.len()..].copy_from_slice(msg);
 
@@ -77,10 +81,12 @@ pub(crate) fn oaep_encrypt<'a, R: CryptoRngCore + ?Sized>(
 
     digest.update(label.as_bytes());
     let mut digest_storage = [0u8; MAX_DIGEST_LEN];
+    let p_hash = digest_storage
+        .get_mut(..h_size)
+        .ok_or(Error::DigestBufferTooSmall)?;
     digest
-        .finalize_into_reset(&mut digest_storage)
+        .finalize_into_reset(p_hash)
         .or(Err(Error::DigestBufferTooSmall))?;
-    let p_hash = &digest_storage[..h_size];
 
     encrypt_internal(
         rng,
@@ -139,3 +145,144 @@ pub(crate) fn oaep_encrypt_digest<
         storage,
     )
 }
+
+/// Unmasks the `k` byte encoded message `em` in place and checks its padding.
+///
+/// All checks are done in constant time. On success, the returned value is
+/// the offset of the message within `em`.
+#[inline]
+fn decrypt_inner<MGF: FnMut(&mut [u8], &mut [u8])>(
+    em: &mut [u8],
+    h_size: usize,
+    expected_p_hash: &[u8],
+    k: usize,
+    mut mgf: MGF,
+) -> Result<CtOption<u32>> {
+    if k < 11 {
+        return Err(Error::Decryption);
+    }
+
+    if k < h_size * 2 + 2 || em.len() != k {
+        return Err(Error::Decryption);
+    }
+
+    let first_byte_is_zero = em[0].ct_eq(&0u8);
+
+    let (_, payload) = em.split_at_mut(1);
+    let (seed, db) = payload.split_at_mut(h_size);
+
+    mgf(seed, db);
+
+    let hash_are_equal = db[0..h_size].ct_eq(expected_p_hash);
+
+    // The remainder of the plaintext must be zero or more 0x00, followed
+    // by 0x01, followed by the message.
+    //   looking_for_index: 1 if we are still looking for the 0x01
+    //   index: the offset of the first 0x01 byte
+    //   nonzero_before_one: 1 if we saw a non-zero byte before the 1
+    let mut looking_for_index = Choice::from(1u8);
+    let mut index = 0u32;
+    let mut nonzero_before_one = Choice::from(0u8);
+
+    for (i, el) in db.iter().skip(h_size).enumerate() {
+        let equals0 = el.ct_eq(&0u8);
+        let equals1 = el.ct_eq(&1u8);
+        index.conditional_assign(&(i as u32), looking_for_index & equals1);
+        looking_for_index &= !equals1;
+        nonzero_before_one |= looking_for_index & !equals0;
+    }
+
+    let valid = first_byte_is_zero & hash_are_equal & !nonzero_before_one & !looking_for_index;
+
+    Ok(CtOption::new(index + 2 + (h_size * 2) as u32, valid))
+}
+
+/// Zeroizes the encoded message `em` and returns `err`.
+///
+/// The unmasked message is as secret as a valid plaintext, so it is not left
+/// in the caller's storage when decryption fails.
+fn reject(em: &mut [u8], err: Error) -> Error {
+    em.zeroize();
+    err
+}
+
+/// Decrypts OAEP padding. `em` holds the `k` bytes of the raw RSA decryption
+/// result and is decoded in place. It is zeroized if decryption fails.
+///
+/// Note that whether this function returns an error or not discloses secret
+/// information. If an attacker can cause this function to run repeatedly and
+/// learn whether each instance returned an error then they can decrypt and
+/// forge signatures as if they had the private key.
+#[inline]
+pub(crate) fn oaep_decrypt<'a>(
+    em: &'a mut [u8],
+    digest: &mut dyn DynDigest,
+    mgf_digest: &mut dyn DynDigest,
+    label: Option<String<128>>,
+    k: usize,
+) -> Result<&'a [u8]> {
+    let h_size = digest.output_size();
+
+    let label = label.unwrap_or_default();
+    if label.len() as u64 >= MAX_LABEL_LEN {
+        return Err(reject(em, Error::LabelTooLong));
+    }
+
+    digest.update(label.as_bytes());
+    let mut digest_storage = [0u8; MAX_DIGEST_LEN];
+    let expected_p_hash = match digest_storage.get_mut(..h_size) {
+        Some(expected_p_hash) => expected_p_hash,
+        None => return Err(reject(em, Error::DigestBufferTooSmall)),
+    };
+    if digest.finalize_into_reset(expected_p_hash).is_err() {
+        return Err(reject(em, Error::DigestBufferTooSmall));
+    }
+
+    let res = decrypt_inner(em, h_size, expected_p_hash, k, |seed, db| {
+        mgf1_xor(seed, mgf_digest, db);
+        mgf1_xor(db, mgf_digest, seed);
+    })
+    .map_err(|err| reject(em, err))?;
+    if res.is_none().into() {
+        return Err(reject(em, Error::Decryption));
+    }
+
+    let index = res.unwrap();
+    Ok(&em[index as usize..])
+}
+
+/// Decrypts OAEP padding. `em` holds the `k` bytes of the raw RSA decryption
+/// result and is decoded in place. It is zeroized if decryption fails.
+///
+/// Note that whether this function returns an error or not discloses secret
+/// information. If an attacker can cause this function to run repeatedly and
+/// learn whether each instance returned an error then they can decrypt and
+/// forge signatures as if they had the private key.
+#[inline]
+pub(crate) fn oaep_decrypt_digest<D: Digest, MGD: Digest + FixedOutputReset>(
+    em: &mut [u8],
+    label: Option<String<128>>,
+    k: usize,
+) -> Result<&[u8]> {
+    let h_size = <D as Digest>::output_size();
+
+    let label = label.unwrap_or_default();
+    if label.len() as u64 >= MAX_LABEL_LEN {
+        return Err(reject(em, Error::LabelTooLong));
+    }
+
+    let expected_p_hash = D::digest(label.as_bytes());
+
+    let res = decrypt_inner(em, h_size, &expected_p_hash, k, |seed, db| {
+        let mut mgf_digest = MGD::new();
+        mgf1_xor_digest(seed, &mut mgf_digest, db);
+        mgf1_xor_digest(db, &mut mgf_digest, seed);
+    })
+    .map_err(|err| reject(em, err))?;
+    if res.is_none().into() {
+        return Err(reject(em, Error::Decryption));
+    }
+
+    let index = res.unwrap();
+    Ok(&em[index as usize..])
+}
@@ -1,7 +1,9 @@
 //! Special handling for converting the BigUint to u8 vectors
 
-use zeroize::Zeroizing;
+use num_traits::NumCast;
+use zeroize::{Zeroize, Zeroizing};
 
+use crate::algorithms::modular::montgomery::type_bits;
 use crate::errors::{Error, Result};
 use crate::traits::UnsignedModularInt;
 use core::borrow::Borrow;
@@ -36,17 +38,47 @@ where
     left_pad(borrow, padded_len, storage)
 }
 
+/// Converts big-endian `input` to `T`, the inverse of [`uint_to_be_pad`].
+///
+/// Leading zeros are allowed, but the value itself must fit in `T`.
+#[inline]
+pub(crate) fn be_bytes_to_uint<T>(input: &[u8]) -> Result<T>
+where
+    T: UnsignedModularInt,
+{
+    let width = type_bits::<T>() / 8;
+    let start = input.len().saturating_sub(width);
+    if input[..start].iter().any(|byte| *byte != 0) {
+        return Err(Error::InvalidPadLen);
+    }
+
+    let mut value = T::zero();
+    for (i, byte) in input[start..].iter().enumerate() {
+        if i > 0 {
+            value = value << 8;
+        }
+        value = value | <T as NumCast>::from(*byte).ok_or(Error::Internal)?;
+    }
+    Ok(value)
+}
+
 /// Converts input to the new vector of the given length, using BE and with 0s left padded.
+///
+/// The intermediate copy of the bytes is zeroized, and the padded bytes are
+/// returned mutably so that secret data can be decoded in place.
 #[inline]
 pub(crate) fn uint_to_zeroizing_be_pad<T>(
     input: T,
     padded_len: usize,
     storage: &mut [u8],
-) -> Result<&[u8]>
+) -> Result<&mut [u8]>
 where
     T: UnsignedModularInt + num_traits::ToBytes,
 {
-    todo!()
+    let mut be_bytes = input.to_be_bytes();
+    let len = left_pad(be_bytes.borrow(), padded_len, storage).map(|padded| padded.len());
+    be_bytes.as_mut().zeroize();
+    Ok(&mut storage[..len?])
 }
 
 #[cfg(test)]
@@ -89,5 +121,31 @@ mod tests {
 
         let padded = uint_to_be_pad(0xDEADBEEF_u32, 4, &mut storage).unwrap();
         assert_eq!(padded, &[0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let padded = uint_to_zeroizing_be_pad(0xDEAD_u16, 3, &mut storage).unwrap();
+        assert_eq!(padded, &[0x00, 0xDE, 0xAD]);
+        assert_eq!(
+            uint_to_zeroizing_be_pad(0xDEAD_u16, 3, &mut storage[..2]),
+            Err(Error::OutputBufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_be_bytes_to_uint() {
+        assert_eq!(
+            be_bytes_to_uint::<u32>(&[0xDE, 0xAD, 0xBE, 0xEF]),
+            Ok(0xDEADBEEF)
+        );
+        assert_eq!(be_bytes_to_uint::<u32>(&[0, 0, 0xDE, 0xAD]), Ok(0xDEAD));
+        assert_eq!(
+            be_bytes_to_uint::<u32>(&[0, 0, 0xDE, 0xAD, 0xBE, 0xEF]),
+            Ok(0xDEADBEEF)
+        );
+        assert_eq!(be_bytes_to_uint::<u32>(&[]), Ok(0));
+        assert_eq!(be_bytes_to_uint::<u8>(&[0, 0x42]), Ok(0x42));
+        assert_eq!(
+            be_bytes_to_uint::<u32>(&[1, 0, 0, 0, 0]),
+            Err(Error::InvalidPadLen)
+        );
     }
 }
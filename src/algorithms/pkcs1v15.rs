@@ -6,13 +6,18 @@
 //!
 //! [RFC8017 § 8.2]: https://datatracker.ietf.org/doc/html/rfc8017#section-8.2
 
+use core::borrow::Borrow;
+
 use const_oid::AssociatedOid;
 use digest::Digest;
 use rand_core::CryptoRngCore;
-use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
-use zeroize::Zeroizing;
+use sha2::Sha256;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeLess, CtOption};
+use zeroize::{Zeroize, Zeroizing};
 
+use crate::traits::UnsignedModularInt;
 use crate::Prefix;
+use pkcs8::ObjectIdentifier;
 
 use crate::errors::{Error, Result};
 
@@ -56,23 +61,285 @@ where
     Ok(em)
 }
 
+/// Number of candidate lengths generated for the alternative message.
+const CANDIDATE_LENGTHS: usize = 128;
+
+/// Hashes the private key values, each as `k` big-endian bytes, into the
+/// secret [`pkcs1v15_implicit_rejection_kdk`] derives the KDK from.
+///
+/// The draft hashes the private exponent `d` alone. Keys without `d` pass
+/// their CRT values instead, each of them is smaller than the modulus too.
+#[inline]
+pub(crate) fn pkcs1v15_implicit_rejection_secret<'a, T>(
+    values: impl IntoIterator<Item = &'a T>,
+    k: usize,
+) -> Zeroizing<[u8; 32]>
+where
+    T: UnsignedModularInt + 'a,
+{
+    let mut hasher = Sha256::new();
+    for value in values {
+        let mut be_bytes = value.to_be_bytes();
+        let bytes: &[u8] = be_bytes.borrow();
+        // value < n, so only leading zeros are cut off.
+        hasher.update(&bytes[bytes.len() - k..]);
+        be_bytes.as_mut().zeroize();
+    }
+    let mut dh = Zeroizing::new([0u8; 32]);
+    dh.copy_from_slice(&hasher.finalize());
+    dh
+}
+
+/// Derives the key derivation key (KDK) for [implicit rejection] from the
+/// digest of the private key, see [`pkcs1v15_implicit_rejection_secret`],
+/// and the ciphertext.
+///
+/// `KDK = HMAC-SHA256(SHA256(d), ciphertext)`
+///
+/// [implicit rejection]: https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-rsa-guidance
+#[inline]
+pub(crate) fn pkcs1v15_implicit_rejection_kdk(
+    secret: &[u8; 32],
+    ciphertext: &[u8],
+) -> Zeroizing<[u8; 32]> {
+    hmac_sha256(secret, &[ciphertext])
+}
+
+/// Removes the encryption padding scheme from PKCS#1 v1.5, with [implicit
+/// rejection] of invalid padding.
+///
+/// `em` holds the `k` bytes of the raw RSA decryption result. If the padding
+/// is invalid, it is overwritten with a pseudo-random message derived from
+/// `kdk`, see [`pkcs1v15_implicit_rejection_kdk`], instead of returning an
+/// error. The padding check and the selection of the message are done in
+/// constant time, only the length of the returned message is observable.
+///
+/// [implicit rejection]: https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-rsa-guidance
+#[inline]
+pub(crate) fn pkcs1v15_encrypt_unpad_implicit_rejection<'a>(
+    em: &'a mut [u8],
+    k: usize,
+    kdk: &[u8; 32],
+) -> Result<&'a [u8]> {
+    let res = decrypt_inner(em, k)?;
+    let valid = res.is_some();
+    let index = res.unwrap_or(0);
+
+    // Messages must leave room for at least 10 bytes of padding, and the
+    // alternative message is no exception.
+    let max_len = (k - 10) as u16;
+    let mut mask = max_len;
+    mask |= mask >> 1;
+    mask |= mask >> 2;
+    mask |= mask >> 4;
+    mask |= mask >> 8;
+
+    let mut candidates = Zeroizing::new([0u8; CANDIDATE_LENGTHS * 2]);
+    implicit_rejection_prf(kdk, b"length", &mut candidates[..]);
+    let mut synthetic_len = 0u16;
+    for candidate in candidates.chunks_exact(2) {
+        let candidate = u16::from_be_bytes([candidate[0], candidate[1]]) & mask;
+        synthetic_len.conditional_assign(&candidate, candidate.ct_lt(&max_len));
+    }
+
+    // Blend in the alternative message one block at a time, so that it never
+    // needs a buffer of its own.
+    let bit_len = k * 8;
+    for (i, chunk) in em.chunks_mut(32).enumerate() {
+        let block = implicit_rejection_prf_block(kdk, i, b"message", bit_len);
+        for (el, synthetic) in chunk.iter_mut().zip(block.iter()) {
+            el.conditional_assign(synthetic, !valid);
+        }
+    }
+
+    let synthetic_index = k as u32 - u32::from(synthetic_len);
+    let index = u32::conditional_select(&synthetic_index, &index, valid);
+    Ok(&em[index as usize..])
+}
+
+/// The pseudo-random function of [implicit rejection], filling `output` with
+/// as many bytes as it holds.
+///
+/// [implicit rejection]: https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-rsa-guidance
+fn implicit_rejection_prf(kdk: &[u8; 32], label: &[u8], output: &mut [u8]) {
+    let bit_len = output.len() * 8;
+    for (i, chunk) in output.chunks_mut(32).enumerate() {
+        let block = implicit_rejection_prf_block(kdk, i, label, bit_len);
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+}
+
+/// Block `i` of the implicit rejection PRF output of `bit_len` bits:
+/// `HMAC-SHA256(kdk, I2OSP(i, 2) || label || I2OSP(bit_len, 2))`.
+fn implicit_rejection_prf_block(
+    kdk: &[u8; 32],
+    i: usize,
+    label: &[u8],
+    bit_len: usize,
+) -> Zeroizing<[u8; 32]> {
+    hmac_sha256(
+        kdk,
+        &[
+            &(i as u16).to_be_bytes(),
+            label,
+            &(bit_len as u16).to_be_bytes(),
+        ],
+    )
+}
+
+/// HMAC-SHA256 of the concatenated `parts`, keyed with `key`.
+fn hmac_sha256(key: &[u8; 32], parts: &[&[u8]]) -> Zeroizing<[u8; 32]> {
+    const IPAD: u8 = 0x36;
+    const OPAD: u8 = 0x5c;
+
+    let mut pad = Zeroizing::new([IPAD; 64]);
+    for (el, key) in pad.iter_mut().zip(key.iter()) {
+        *el ^= key;
+    }
+    let mut inner = Sha256::new_with_prefix(&pad[..]);
+    for part in parts {
+        inner.update(part);
+    }
+    let mut inner_hash = Zeroizing::new([0u8; 32]);
+    inner_hash.copy_from_slice(&inner.finalize());
+
+    for el in pad.iter_mut() {
+        *el ^= IPAD ^ OPAD;
+    }
+    let mut outer = Sha256::new_with_prefix(&pad[..]);
+    outer.update(&inner_hash[..]);
+    let mut mac = Zeroizing::new([0u8; 32]);
+    mac.copy_from_slice(&outer.finalize());
+    mac
+}
+
+/// Length of a TLS pre-master secret.
+pub(crate) const TLS_PRE_MASTER_SECRET_LEN: usize = 48;
+
+/// Removes the encryption padding scheme from PKCS#1 v1.5 from an encrypted
+/// TLS pre-master secret, following [RFC 5246 § 7.4.7.1].
+///
+/// `em` holds the `k` bytes of the raw RSA decryption result. If the padding
+/// is valid and the message is 48 bytes long, the result is `client_version`
+/// followed by the last 46 bytes of the message. Otherwise it is `random`.
+/// Neither the padding check nor the selection reveals which one it was.
+///
+/// [RFC 5246 § 7.4.7.1]: https://datatracker.ietf.org/doc/html/rfc5246#section-7.4.7.1
 #[inline]
-pub(crate) fn pkcs1v15_sign_unpad(prefix: &[u8], hashed: &[u8], em: &[u8], k: usize) -> Result<()> {
+pub(crate) fn pkcs1v15_encrypt_unpad_tls_pre_master_secret(
+    em: &[u8],
+    k: usize,
+    client_version: [u8; 2],
+    random: &[u8; TLS_PRE_MASTER_SECRET_LEN],
+) -> Result<[u8; TLS_PRE_MASTER_SECRET_LEN]> {
+    if k < TLS_PRE_MASTER_SECRET_LEN + 11 {
+        return Err(Error::Decryption);
+    }
+
+    let res = decrypt_inner(em, k)?;
+    let start = k - TLS_PRE_MASTER_SECRET_LEN;
+    let valid = res.is_some() & res.unwrap_or(0).ct_eq(&(start as u32));
+
+    let msg = &em[start..];
+    let mut pre_master_secret = *random;
+    for (i, el) in pre_master_secret.iter_mut().enumerate() {
+        let value = if i < client_version.len() {
+            client_version[i]
+        } else {
+            msg[i]
+        };
+        el.conditional_assign(&value, valid);
+    }
+    Ok(pre_master_secret)
+}
+
+/// Removes the PKCS1v15 padding It moves the index of the first byte of the
+/// message, which is only valid if the returned option is some.
+///
+/// The padding is checked in constant time.
+#[inline]
+fn decrypt_inner(em: &[u8], k: usize) -> Result<CtOption<u32>> {
+    if k < 11 || em.len() != k {
+        return Err(Error::Decryption);
+    }
+
+    let first_byte_is_zero = em[0].ct_eq(&0u8);
+    let second_byte_is_two = em[1].ct_eq(&2u8);
+
+    // The remainder of the plaintext must be a string of non-zero random
+    // octets, followed by a 0, followed by the message.
+    //   looking_for_index: 1 iff we are still looking for the zero.
+    //   index: the offset of the first zero byte.
+    let mut looking_for_index = Choice::from(1u8);
+    let mut index = 0u32;
+
+    for (i, el) in em.iter().enumerate().skip(2) {
+        let equals0 = el.ct_eq(&0u8);
+        index.conditional_assign(&(i as u32), looking_for_index & equals0);
+        looking_for_index &= !equals0;
+    }
+
+    // The PS padding must be at least 8 bytes long, and it starts two
+    // bytes into em.
+    let valid_ps = !index.ct_lt(&(2 + 8));
+    let valid = first_byte_is_zero & second_byte_is_two & !looking_for_index & valid_ps;
+
+    Ok(CtOption::new(index + 1, valid))
+}
+
+/// Encodes `prefix || hashed` as `EM` for signing with PKCS#1 v1.5 into
+/// `storage`, returning the `k` bytes of `EM`.
+#[inline]
+pub(crate) fn pkcs1v15_sign_pad<'a>(
+    prefix: &[u8],
+    hashed: &[u8],
+    k: usize,
+    storage: &'a mut [u8],
+) -> Result<&'a [u8]> {
     let hash_len = hashed.len();
     let t_len = prefix.len() + hashed.len();
     if k < t_len + 11 {
-        return Err(Error::Verification);
+        return Err(Error::MessageTooLong);
     }
 
     // EM = 0x00 || 0x01 || PS || 0x00 || T
-    let mut ok = em[0].ct_eq(&0u8);
-    ok &= em[1].ct_eq(&1u8);
-    ok &= em[k - hash_len..k].ct_eq(hashed);
-    ok &= em[k - t_len..k - hash_len].ct_eq(prefix);
-    ok &= em[k - t_len - 1].ct_eq(&0u8);
+    let em = storage.get_mut(..k).ok_or(Error::OutputBufferTooSmall)?;
+    em[0] = 0;
+    em[1] = 1;
+    em[2..k - t_len - 1].fill(0xff);
+    em[k - t_len - 1] = 0;
+    em[k - t_len..k - hash_len].copy_from_slice(prefix);
+    em[k - hash_len..k].copy_from_slice(hashed);
+    Ok(em)
+}
 
-    for el in em.iter().skip(2).take(k - t_len - 3) {
-        ok &= el.ct_eq(&0xff)
+/// Verifies `em` as in [RFC8017 § 8.2.2]: the expected `EM` is encoded from
+/// `prefix || hashed` into `storage` and compared with `em` as a whole, in
+/// constant time, instead of parsing `em`.
+///
+/// If `accept_missing_null` is set, `em` may also hold a `DigestInfo` whose
+/// `AlgorithmIdentifier` lacks the NULL parameters.
+///
+/// [RFC8017 § 8.2.2]: https://datatracker.ietf.org/doc/html/rfc8017#section-8.2.2
+pub(crate) fn pkcs1v15_sign_verify(
+    prefix: &[u8],
+    hashed: &[u8],
+    em: &[u8],
+    accept_missing_null: bool,
+    storage: &mut [u8],
+) -> Result<()> {
+    let k = em.len();
+    let mut ok = match pkcs1v15_sign_pad(prefix, hashed, k, storage) {
+        Err(Error::MessageTooLong) => return Err(Error::Verification),
+        expected => em.ct_eq(expected?),
+    };
+
+    if accept_missing_null {
+        if let Some(prefix) = pkcs1v15_prefix_without_null(prefix) {
+            if let Ok(expected) = pkcs1v15_sign_pad(&prefix, hashed, k, storage) {
+                ok |= em.ct_eq(expected);
+            }
+        }
     }
 
     if ok.unwrap_u8() != 1 {
@@ -82,6 +349,100 @@ pub(crate) fn pkcs1v15_sign_unpad(prefix: &[u8], hashed: &[u8], em: &[u8], k: us
     Ok(())
 }
 
+/// Strictly parses `em` as an EMSA-PKCS1-v1_5 encoded message and returns
+/// the hash OID and digest of its `DigestInfo`:
+///
+/// EM = 0x00 || 0x01 || PS || 0x00 || T
+///
+/// T = 0x30 <alg_len + 2 + digest_len> 0x30 <oid_len + 4> 0x06 <oid_len> oid 0x05 0x00 0x04 <digest_len> digest
+///
+/// `PS` must be at least eight 0xff bytes and all lengths must be in the short
+/// DER form. The NULL parameters may only be left out if `accept_missing_null`
+/// is set.
+pub(crate) fn pkcs1v15_sign_open(
+    em: &[u8],
+    accept_missing_null: bool,
+) -> Result<(ObjectIdentifier, &[u8])> {
+    if em.len() < 11 || em[0] != 0x00 || em[1] != 0x01 {
+        return Err(Error::Verification);
+    }
+    let ps_len = em[2..].iter().take_while(|el| **el == 0xff).count();
+    if ps_len < 8 || em.get(2 + ps_len) != Some(&0x00) {
+        return Err(Error::Verification);
+    }
+    let t = &em[3 + ps_len..];
+
+    let digest_info = der_short_tlv(t, 0x30).ok_or(Error::Verification)?;
+    let (algorithm, rest) = der_short_tlv_split(digest_info, 0x30).ok_or(Error::Verification)?;
+    let (oid, parameters) = der_short_tlv_split(algorithm, 0x06).ok_or(Error::Verification)?;
+    let digest = der_short_tlv(rest, 0x04).ok_or(Error::Verification)?;
+    if t.len() != digest_info.len() + 2 {
+        return Err(Error::Verification);
+    }
+    match parameters {
+        [0x05, 0x00] => {}
+        [] if accept_missing_null => {}
+        _ => return Err(Error::Verification),
+    }
+
+    let oid = ObjectIdentifier::from_bytes(oid).or(Err(Error::Verification))?;
+    Ok((oid, digest))
+}
+
+/// Splits a DER TLV with the given `tag` and a short form length off `data`,
+/// returning its value and what follows it.
+fn der_short_tlv_split(data: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    match data {
+        [t, len, rest @ ..] if *t == tag && *len < 0x80 && usize::from(*len) <= rest.len() => {
+            Some(rest.split_at(usize::from(*len)))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the value of the DER TLV with the given `tag` and a short form
+/// length that makes up all of `data`.
+fn der_short_tlv(data: &[u8], tag: u8) -> Option<&[u8]> {
+    match der_short_tlv_split(data, tag)? {
+        (value, []) => Some(value),
+        _ => None,
+    }
+}
+
+/// Turns a `DigestInfo` prefix as built by [`pkcs1v15_generate_prefix`] into
+/// the same prefix without the NULL parameters:
+///
+/// prefix = 0x30 <oid_len + 6 + digest_len> 0x30 <oid_len + 2> 0x06 <oid_len> oid 0x04 <digest_len>
+///
+/// Returns `None` if `prefix` does not have that shape, e.g. when it is empty.
+fn pkcs1v15_prefix_without_null(prefix: &[u8]) -> Option<Prefix> {
+    if prefix.len() < 6 {
+        return None;
+    }
+    let (header, rest) = prefix.split_at(6);
+    let oid_len = usize::from(header[5]);
+    if header[0] != 0x30 || header[2] != 0x30 || header[4] != 0x06 || rest.len() != oid_len + 4 {
+        return None;
+    }
+    let (oid, tail) = rest.split_at(oid_len);
+    if tail[..3] != [0x05, 0x00, 0x04] {
+        return None;
+    }
+
+    let mut v = Prefix::new();
+    v.extend([
+        0x30,
+        header[1].checked_sub(2)?,
+        0x30,
+        header[3].checked_sub(2)?,
+        0x6,
+        header[5],
+    ]);
+    v.extend_from_slice(oid).ok()?;
+    v.extend_from_slice(&[0x04, tail[3]]).ok()?;
+    Some(v)
+}
+
 /// prefix = 0x30 <oid_len + 8 + digest_len> 0x30 <oid_len + 4> 0x06 <oid_len> oid 0x05 0x00 0x04 <digest_len>
 #[inline]
 pub(crate) fn pkcs1v15_generate_prefix<D>() -> Prefix
@@ -108,8 +469,228 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use hex_literal::hex;
     use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
 
+    #[test]
+    fn test_sign_pad_unpad() {
+        let prefix = [0x30, 0x31];
+        let hashed = [0xAB; 20];
+        let mut storage = [0x55u8; 40];
+        let em = pkcs1v15_sign_pad(&prefix, &hashed, 34, &mut storage).unwrap();
+        assert_eq!(em.len(), 34);
+        assert_eq!(
+            em[..12],
+            [0, 1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0]
+        );
+        let em: [u8; 34] = em.try_into().unwrap();
+        let mut expected = [0u8; 34];
+        assert!(pkcs1v15_sign_verify(&prefix, &hashed, &em, false, &mut expected).is_ok());
+
+        assert_eq!(
+            pkcs1v15_sign_pad(&prefix, &hashed, 32, &mut storage),
+            Err(Error::MessageTooLong)
+        );
+        assert_eq!(
+            pkcs1v15_sign_pad(&prefix, &hashed, 34, &mut [0u8; 33]),
+            Err(Error::OutputBufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_sign_verify_encode_and_compare() {
+        let hashed = [0xAB; 20];
+        let prefix = pkcs1v15_generate_prefix::<sha1::Sha1>();
+        let mut storage = [0u8; 64];
+        let mut expected = [0u8; 64];
+        let mut em = [0u8; 64];
+        em.copy_from_slice(pkcs1v15_sign_pad(&prefix, &hashed, 64, &mut storage).unwrap());
+        assert!(pkcs1v15_sign_verify(&prefix, &hashed, &em, false, &mut expected).is_ok());
+
+        // Any change of the encoded message is rejected, be it in the
+        // padding, the DigestInfo or the hash
+        for i in 0..em.len() {
+            let mut bad = em;
+            bad[i] ^= 0x01;
+            assert_eq!(
+                pkcs1v15_sign_verify(&prefix, &hashed, &bad, false, &mut expected),
+                Err(Error::Verification)
+            );
+        }
+
+        // A modulus too small for the DigestInfo
+        assert_eq!(
+            pkcs1v15_sign_verify(&prefix, &hashed, &em[..45], false, &mut expected),
+            Err(Error::Verification)
+        );
+        assert_eq!(
+            pkcs1v15_sign_verify(&prefix, &hashed, &em, false, &mut expected[..63]),
+            Err(Error::OutputBufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_sign_verify_missing_null() {
+        let hashed = [0xAB; 20];
+        let prefix = pkcs1v15_generate_prefix::<sha1::Sha1>();
+        let no_null = pkcs1v15_prefix_without_null(&prefix).unwrap();
+        assert_eq!(no_null[..], hex!("301f300706052b0e03021a0414")[..]);
+        assert_eq!(pkcs1v15_prefix_without_null(&[]), None);
+        assert_eq!(pkcs1v15_prefix_without_null(&no_null), None);
+
+        let mut storage = [0u8; 64];
+        let mut expected = [0u8; 64];
+        let mut em = [0u8; 64];
+        em.copy_from_slice(pkcs1v15_sign_pad(&no_null, &hashed, 64, &mut storage).unwrap());
+        assert_eq!(
+            pkcs1v15_sign_verify(&prefix, &hashed, &em, false, &mut expected),
+            Err(Error::Verification)
+        );
+        assert!(pkcs1v15_sign_verify(&prefix, &hashed, &em, true, &mut expected).is_ok());
+        assert_eq!(
+            pkcs1v15_sign_verify(&prefix, &[0xAC; 20], &em, true, &mut expected),
+            Err(Error::Verification)
+        );
+    }
+
+    #[test]
+    fn test_sign_open() {
+        let hashed = [0xAB; 20];
+        let prefix = pkcs1v15_generate_prefix::<sha1::Sha1>();
+        let no_null = pkcs1v15_prefix_without_null(&prefix).unwrap();
+        let mut storage = [0u8; 64];
+
+        let em = pkcs1v15_sign_pad(&prefix, &hashed, 64, &mut storage).unwrap();
+        assert_eq!(
+            pkcs1v15_sign_open(em, false),
+            Ok((<sha1::Sha1 as AssociatedOid>::OID, &hashed[..]))
+        );
+
+        let em = pkcs1v15_sign_pad(&no_null, &hashed, 64, &mut storage).unwrap();
+        assert_eq!(pkcs1v15_sign_open(em, false), Err(Error::Verification));
+        assert_eq!(
+            pkcs1v15_sign_open(em, true),
+            Ok((<sha1::Sha1 as AssociatedOid>::OID, &hashed[..]))
+        );
+
+        // Unprefixed and malformed encodings
+        let em = pkcs1v15_sign_pad(&[], &hashed, 64, &mut storage).unwrap();
+        assert_eq!(pkcs1v15_sign_open(em, true), Err(Error::Verification));
+        let mut em = [0u8; 64];
+        em.copy_from_slice(pkcs1v15_sign_pad(&prefix, &hashed, 64, &mut storage).unwrap());
+        for (i, value) in [
+            (0, 0x01),
+            (1, 0x02),
+            (2, 0xfe),
+            (28, 0x01),
+            (29, 0x31),
+            (30, 0x22),
+        ] {
+            let mut bad = em;
+            bad[i] = value;
+            assert_eq!(pkcs1v15_sign_open(&bad, false), Err(Error::Verification));
+        }
+
+        // PS must be at least eight bytes long
+        let mut em = [0xffu8; 46];
+        em[0] = 0x00;
+        em[1] = 0x01;
+        em[10] = 0x00;
+        em[11..26].copy_from_slice(&prefix);
+        em[26..].copy_from_slice(&hashed);
+        assert!(pkcs1v15_sign_open(&em, false).is_ok());
+        assert_eq!(
+            pkcs1v15_sign_open(&em[1..], false),
+            Err(Error::Verification)
+        );
+        let mut short = [0u8; 45];
+        short[..2].copy_from_slice(&em[..2]);
+        short[2..].copy_from_slice(&em[3..]);
+        assert_eq!(pkcs1v15_sign_open(&short, false), Err(Error::Verification));
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 1, with the 20 byte key padded to 32 bytes.
+        let mut key = [0u8; 32];
+        key[..20].fill(0x0b);
+        assert_eq!(
+            *hmac_sha256(&key, &[b"Hi ", b"There"]),
+            hex!("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7")
+        );
+    }
+
+    #[test]
+    fn test_implicit_rejection_unpad() {
+        let kdk = [0x42; 32];
+        let mut em = [0x55u8; 32];
+        em[0] = 0;
+        em[1] = 2;
+        em[20] = 0;
+        assert_eq!(
+            pkcs1v15_encrypt_unpad_implicit_rejection(&mut em, 32, &kdk).unwrap(),
+            [0x55; 11]
+        );
+
+        // Any invalid padding, here a padding string of only 7 bytes, gives
+        // the same alternative message for the same KDK.
+        em[9] = 0;
+        let synthetic = pkcs1v15_encrypt_unpad_implicit_rejection(&mut em, 32, &kdk)
+            .unwrap()
+            .to_vec();
+        assert!(synthetic.len() <= 32 - 11);
+        let mut em = [0x55u8; 32];
+        em[0] = 0;
+        em[1] = 2;
+        assert_eq!(
+            pkcs1v15_encrypt_unpad_implicit_rejection(&mut em, 32, &kdk).unwrap(),
+            synthetic
+        );
+
+        assert_eq!(
+            pkcs1v15_encrypt_unpad_implicit_rejection(&mut em, 10, &kdk),
+            Err(Error::Decryption)
+        );
+    }
+
+    #[test]
+    fn test_tls_pre_master_secret_unpad() {
+        let random = [0xAA; 48];
+        let mut em = [0x55u8; 64];
+        em[0] = 0;
+        em[1] = 2;
+        em[15] = 0;
+        em[16..18].copy_from_slice(&[3, 1]);
+        let mut expected = [0x55; 48];
+        expected[..2].copy_from_slice(&[3, 3]);
+        assert_eq!(
+            pkcs1v15_encrypt_unpad_tls_pre_master_secret(&em, 64, [3, 3], &random),
+            Ok(expected)
+        );
+
+        // A message of the wrong length
+        em[15] = 0x55;
+        em[14] = 0;
+        assert_eq!(
+            pkcs1v15_encrypt_unpad_tls_pre_master_secret(&em, 64, [3, 3], &random),
+            Ok(random)
+        );
+
+        // Invalid padding
+        em[14] = 0x55;
+        em[15] = 0;
+        em[1] = 1;
+        assert_eq!(
+            pkcs1v15_encrypt_unpad_tls_pre_master_secret(&em, 64, [3, 3], &random),
+            Ok(random)
+        );
+
+        assert_eq!(
+            pkcs1v15_encrypt_unpad_tls_pre_master_secret(&em[..58], 58, [3, 3], &random),
+            Err(Error::Decryption)
+        );
+    }
+
     #[test]
     fn test_non_zero_bytes() {
         for _ in 0..10 {
@@ -10,10 +10,11 @@
 //! [RFC8017 § 8.1]: https://datatracker.ietf.org/doc/html/rfc8017#section-8.1
 
 use digest::{Digest, DynDigest, FixedOutputReset};
-use subtle::{Choice, ConstantTimeEq};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeLess};
 
 use super::mgf::{mgf1_xor, mgf1_xor_digest};
 use crate::errors::{Error, Result};
+use crate::pss::SaltLength;
 
 use core::marker::PhantomData;
 
@@ -21,28 +22,173 @@ use core::marker::PhantomData;
 // This gets temporarily stack-allocated
 const MAX_DIGEST_LEN: usize = 64;
 
-pub(crate) fn emsa_pss_encode(
+/// Checks the lengths for encoding a message of `em_bits` bits, as in
+/// [RFC8017 § 9.1.1] steps 1-3, and returns the `emLen` long part of `storage`
+/// that will hold the encoded message.
+///
+/// [RFC8017 § 9.1.1]: https://datatracker.ietf.org/doc/html/rfc8017#section-9.1.1
+fn emsa_pss_encode_pre<'a>(
     m_hash: &[u8],
     em_bits: usize,
-    salt: &[u8],
+    s_len: usize,
+    h_len: usize,
+    storage: &'a mut [u8],
+) -> Result<&'a mut [u8]> {
+    // 1. If the length of M is greater than the input limitation for the
+    //    hash function (2^61 - 1 octets for SHA-1), output "message too
+    //    long" and stop.
+    //
+    // 2. Let mHash = Hash(M), an octet string of length hLen.
+    if m_hash.len() != h_len {
+        return Err(Error::InputNotHashed);
+    }
+
+    // 3. If em_len < h_len + s_len + 2, output "encoding error" and stop.
+    let em_len = (em_bits + 7) / 8;
+    if em_len < h_len + s_len + 2 {
+        // The key is too small for this digest and salt length
+        return Err(Error::Internal);
+    }
+
+    storage.get_mut(..em_len).ok_or(Error::OutputBufferTooSmall)
+}
+
+/// Returns the part of `storage` where [`emsa_pss_encode`] and
+/// [`emsa_pss_encode_digest`] expect a salt of `s_len` bytes.
+///
+/// The salt is placed directly at its final position in `DB`, so that no
+/// separate buffer is needed for it.
+pub(crate) fn emsa_pss_salt_mut(
+    storage: &mut [u8],
+    em_bits: usize,
+    s_len: usize,
+    h_len: usize,
+) -> Result<&mut [u8]> {
+    let em_len = (em_bits + 7) / 8;
+    if em_len < h_len + s_len + 2 {
+        return Err(Error::Internal);
+    }
+    let db_len = em_len - h_len - 1;
+    storage
+        .get_mut(db_len - s_len..db_len)
+        .ok_or(Error::OutputBufferTooSmall)
+}
+
+/// Builds `DB = PS || 0x01 || salt` in place around the salt and splits `em`
+/// into `DB`, `H` and the trailer, which is set to `0xbc`.
+fn emsa_pss_encode_db(em: &mut [u8], s_len: usize, h_len: usize) -> (&mut [u8], &mut [u8]) {
+    let em_len = em.len();
+    let (db, h) = em.split_at_mut(em_len - h_len - 1);
+    let (h, trailer) = h.split_at_mut(h_len);
+
+    // 7. Generate an octet string PS consisting of em_len - s_len - h_len - 2
+    //    zero octets. The length of PS may be 0.
+    //
+    // 8. Let DB = PS || 0x01 || salt; DB is an octet string of length
+    //    emLen - hLen - 1.
+    let ps_len = em_len - s_len - h_len - 2;
+    for byte in &mut db[..ps_len] {
+        *byte = 0;
+    }
+    db[ps_len] = 0x01;
+
+    // 12. Let EM = maskedDB || H || 0xbc.
+    trailer[0] = 0xBC;
+
+    (db, h)
+}
+
+/// Encodes `m_hash` with EMSA-PSS into `storage`, returning the `emLen` bytes
+/// of the encoded message.
+///
+/// The salt of `s_len` bytes must already have been written to the slot
+/// returned by [`emsa_pss_salt_mut`].
+pub(crate) fn emsa_pss_encode<'a>(
+    m_hash: &[u8],
+    em_bits: usize,
+    s_len: usize,
     hash: &mut dyn DynDigest,
-) -> Result<()> {
-    todo!()
+    storage: &'a mut [u8],
+) -> Result<&'a [u8]> {
+    let h_len = hash.output_size();
+    let em = emsa_pss_encode_pre(m_hash, em_bits, s_len, h_len, storage)?;
+    let em_len = em.len();
+    let (db, h) = emsa_pss_encode_db(&mut *em, s_len, h_len);
+
+    // 4. Generate a random octet string salt of length s_len; if s_len = 0,
+    //    then salt is the empty string. This was done by the caller.
+    //
+    // 5.  Let
+    //       M' = (0x)00 00 00 00 00 00 00 00 || m_hash || salt;
+    //
+    //     M' is an octet string of length 8 + h_len + s_len with eight
+    //     initial zero octets.
+    //
+    // 6.  Let H = Hash(M'), an octet string of length h_len.
+    let prefix = [0u8; 8];
+
+    hash.update(&prefix);
+    hash.update(m_hash);
+    hash.update(&db[db.len() - s_len..]);
+    hash.finalize_into_reset(h)
+        .or(Err(Error::DigestBufferTooSmall))?;
+
+    // 9.  Let dbMask = MGF(H, emLen - hLen - 1).
+    //
+    // 10. Let maskedDB = DB \xor dbMask.
+    mgf1_xor(db, hash, h);
+
+    // 11. Set the leftmost 8 * em_len - em_bits bits of the leftmost octet in
+    //     maskedDB to zero.
+    db[0] &= 0xFF >> (8 * em_len - em_bits);
+
+    Ok(em)
 }
 
-pub(crate) fn emsa_pss_encode_digest<D>(m_hash: &[u8], em_bits: usize, salt: &[u8]) -> Result<()>
+/// Encodes `m_hash` with EMSA-PSS into `storage`, like [`emsa_pss_encode`].
+pub(crate) fn emsa_pss_encode_digest<'a, D>(
+    m_hash: &[u8],
+    em_bits: usize,
+    s_len: usize,
+    storage: &'a mut [u8],
+) -> Result<&'a [u8]>
 where
     D: Digest + FixedOutputReset,
 {
-    let _ = PhantomData::<D>;
-    todo!()
+    let h_len = <D as Digest>::output_size();
+    let em = emsa_pss_encode_pre(m_hash, em_bits, s_len, h_len, storage)?;
+    let em_len = em.len();
+    let (db, h) = emsa_pss_encode_db(&mut *em, s_len, h_len);
+
+    let mut hash = D::new();
+
+    // 5. Let M' = (0x)00 00 00 00 00 00 00 00 || m_hash || salt;
+    //
+    // 6. Let H = Hash(M'), an octet string of length h_len.
+    let prefix = [0u8; 8];
+
+    Digest::update(&mut hash, prefix);
+    Digest::update(&mut hash, m_hash);
+    Digest::update(&mut hash, &db[db.len() - s_len..]);
+    h.copy_from_slice(&hash.finalize_reset());
+
+    // 9.  Let dbMask = MGF(H, emLen - hLen - 1).
+    //
+    // 10. Let maskedDB = DB \xor dbMask.
+    mgf1_xor_digest(db, &mut hash, h);
+
+    // 11. Set the leftmost 8 * em_len - em_bits bits of the leftmost octet in
+    //     maskedDB to zero.
+    db[0] &= 0xFF >> (8 * em_len - em_bits);
+
+    Ok(em)
 }
 
 fn emsa_pss_verify_pre<'a>(
     m_hash: &[u8],
     em: &'a mut [u8],
     em_bits: usize,
-    s_len: usize,
+    salt_len: SaltLength,
     h_len: usize,
 ) -> Result<(&'a mut [u8], &'a mut [u8])> {
     // 1. If the length of M is greater than the input limitation for the
@@ -55,6 +201,11 @@ fn emsa_pss_verify_pre<'a>(
     }
 
     // 3. If emLen < hLen + sLen + 2, output "inconsistent" and stop.
+    //    A recovered salt must be at least as long as the minimum.
+    let s_len = match salt_len {
+        SaltLength::Exact(s_len) => s_len,
+        SaltLength::Auto { min_len } => min_len,
+    };
     let em_len = em.len(); //(em_bits + 7) / 8;
     if em_len < h_len + s_len + 2 {
         return Err(Error::Verification);
@@ -86,26 +237,61 @@ fn emsa_pss_verify_pre<'a>(
     Ok((db, h))
 }
 
-fn emsa_pss_verify_salt(db: &[u8], em_len: usize, s_len: usize, h_len: usize) -> Choice {
-    // 10. If the emLen - hLen - sLen - 2 leftmost octets of DB are not zero
-    //     or if the octet at position emLen - hLen - sLen - 1 (the leftmost
-    //     position is "position 1") does not have hexadecimal value 0x01,
-    //     output "inconsistent" and stop.
-    let (zeroes, rest) = db.split_at(em_len - h_len - s_len - 2);
-    let valid: Choice = zeroes
-        .iter()
-        .fold(Choice::from(1u8), |a, e| a & e.ct_eq(&0x00));
-
-    valid & rest[0].ct_eq(&0x01)
+/// Checks the padding in front of the salt in `DB`, returning whether it is
+/// valid and the length of the salt.
+fn emsa_pss_verify_salt(db: &[u8], salt_len: SaltLength) -> (Choice, usize) {
+    match salt_len {
+        SaltLength::Exact(s_len) => {
+            // 10. If the emLen - hLen - sLen - 2 leftmost octets of DB are not zero
+            //     or if the octet at position emLen - hLen - sLen - 1 (the leftmost
+            //     position is "position 1") does not have hexadecimal value 0x01,
+            //     output "inconsistent" and stop.
+            let (zeroes, rest) = db.split_at(db.len() - s_len - 1);
+            let valid: Choice = zeroes
+                .iter()
+                .fold(Choice::from(1u8), |a, e| a & e.ct_eq(&0x00));
+
+            (valid & rest[0].ct_eq(&0x01), s_len)
+        }
+        SaltLength::Auto { min_len } => emsa_pss_recover_salt_len(db, min_len),
+    }
+}
+
+/// Recovers the salt length from the position of the `0x01` separator in
+/// `DB = PS || 0x01 || salt`, in constant time.
+///
+/// Returns whether the padding is valid and the salt at least `min_len`
+/// bytes long, along with the salt length.
+fn emsa_pss_recover_salt_len(db: &[u8], min_len: usize) -> (Choice, usize) {
+    //   looking_for_index: 1 if we are still looking for the 0x01
+    //   index: the offset of the first 0x01 byte
+    //   nonzero_before_one: 1 if we saw a byte other than 0x00 before the 0x01
+    let mut looking_for_index = Choice::from(1u8);
+    let mut index = 0u32;
+    let mut nonzero_before_one = Choice::from(0u8);
+
+    for (i, el) in db.iter().enumerate() {
+        let equals0 = el.ct_eq(&0u8);
+        let equals1 = el.ct_eq(&1u8);
+        index.conditional_assign(&(i as u32), looking_for_index & equals1);
+        nonzero_before_one |= looking_for_index & !equals0 & !equals1;
+        looking_for_index &= !equals1;
+    }
+
+    let s_len = db.len() as u32 - 1 - index;
+    let valid = !looking_for_index & !nonzero_before_one & !s_len.ct_lt(&(min_len as u32));
+    (valid, s_len as usize)
 }
 
+/// Verifies the EMSA-PSS encoded message `em`, returning the length of the
+/// salt.
 pub(crate) fn emsa_pss_verify(
     m_hash: &[u8],
     em: &mut [u8],
-    s_len: usize,
+    salt_len: SaltLength,
     hash: &mut dyn DynDigest,
     key_bits: usize,
-) -> Result<()> {
+) -> Result<usize> {
     let em_bits = key_bits - 1;
     let em_len = (em_bits + 7) / 8;
     let key_len = (key_bits + 7) / 8;
@@ -113,7 +299,7 @@ pub(crate) fn emsa_pss_verify(
 
     let em = &mut em[key_len - em_len..];
 
-    let (db, h) = emsa_pss_verify_pre(m_hash, em, em_bits, s_len, h_len)?;
+    let (db, h) = emsa_pss_verify_pre(m_hash, em, em_bits, salt_len, h_len)?;
 
     // 7. Let dbMask = MGF(H, em_len - h_len - 1)
     //
@@ -124,7 +310,7 @@ pub(crate) fn emsa_pss_verify(
     //     to zero.
     db[0] &= 0xFF >> /*uint*/(8 * em_len - em_bits);
 
-    let salt_valid = emsa_pss_verify_salt(db, em_len, s_len, h_len);
+    let (salt_valid, s_len) = emsa_pss_verify_salt(db, salt_len);
 
     // 11. Let salt be the last s_len octets of DB.
     let salt = &db[db.len() - s_len..];
@@ -141,24 +327,28 @@ pub(crate) fn emsa_pss_verify(
     hash.update(m_hash);
     hash.update(salt);
     let mut digest_storage = [0u8; MAX_DIGEST_LEN];
-    hash.finalize_into_reset(&mut digest_storage)
+    let h0 = digest_storage
+        .get_mut(..h_len)
+        .ok_or(Error::DigestBufferTooSmall)?;
+    hash.finalize_into_reset(h0)
         .or(Err(Error::DigestBufferTooSmall))?;
-    let h0 = &digest_storage[..h_len];
 
     // 14. If H = H', output "consistent." Otherwise, output "inconsistent."
     if (salt_valid & h0.ct_eq(h)).into() {
-        Ok(())
+        Ok(s_len)
     } else {
         Err(Error::Verification)
     }
 }
 
+/// Verifies the EMSA-PSS encoded message `em`, returning the length of the
+/// salt, like [`emsa_pss_verify`].
 pub(crate) fn emsa_pss_verify_digest<D>(
     m_hash: &[u8],
     em: &mut [u8],
-    s_len: usize,
+    salt_len: SaltLength,
     key_bits: usize,
-) -> Result<()>
+) -> Result<usize>
 where
     D: Digest + FixedOutputReset,
 {
@@ -169,7 +359,7 @@ where
 
     let em = &mut em[key_len - em_len..];
 
-    let (db, h) = emsa_pss_verify_pre(m_hash, em, em_bits, s_len, h_len)?;
+    let (db, h) = emsa_pss_verify_pre(m_hash, em, em_bits, salt_len, h_len)?;
 
     let mut hash = D::new();
 
@@ -182,7 +372,7 @@ where
     //     to zero.
     db[0] &= 0xFF >> /*uint*/(8 * em_len - em_bits);
 
-    let salt_valid = emsa_pss_verify_salt(db, em_len, s_len, h_len);
+    let (salt_valid, s_len) = emsa_pss_verify_salt(db, salt_len);
 
     // 11. Let salt be the last s_len octets of DB.
     let salt = &db[db.len() - s_len..];
@@ -202,7 +392,7 @@ where
 
     // 14. If H = H', output "consistent." Otherwise, output "inconsistent."
     if (salt_valid & h0.ct_eq(h)).into() {
-        Ok(())
+        Ok(s_len)
     } else {
         Err(Error::Verification)
     }
@@ -0,0 +1,77 @@
+//! Random integer generation for generic `T`.
+
+use num_traits::NumCast;
+use rand_core::CryptoRngCore;
+
+use crate::traits::UnsignedModularInt;
+
+/// Generates a uniformly random value with at most `bits` significant bits.
+///
+/// `bits` must not exceed the width of `T`.
+pub(crate) fn random_bits<T, R>(rng: &mut R, bits: usize) -> T
+where
+    T: UnsignedModularInt,
+    R: CryptoRngCore + ?Sized,
+{
+    let mut value = T::zero();
+    let mut byte = [0u8; 1];
+    for i in 0..(bits + 7) / 8 {
+        rng.fill_bytes(&mut byte);
+        if i > 0 {
+            value = value << 8;
+        }
+        value = value | <T as NumCast>::from(byte[0]).unwrap_or_else(T::zero);
+    }
+    byte.fill(0);
+
+    if bits % 8 != 0 {
+        let width = T::zero().count_zeros() as usize;
+        value = value & (T::max_value() >> (width - bits));
+    }
+    value
+}
+
+/// Generates a uniformly random value in `[1, bound)` by rejection sampling.
+///
+/// `bound` must be greater than one.
+pub(crate) fn random_nonzero_below<T, R>(rng: &mut R, bound: &T) -> T
+where
+    T: UnsignedModularInt,
+    R: CryptoRngCore + ?Sized,
+{
+    let bits = bound.bits();
+    loop {
+        let value: T = random_bits(rng, bits);
+        if !value.is_zero() && value < *bound {
+            return value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    #[test]
+    fn test_random_bits() {
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        for bits in 1..=64 {
+            for _ in 0..10 {
+                let value: u64 = random_bits(&mut rng, bits);
+                assert!(value.bits() <= bits);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_nonzero_below() {
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        for bound in 2..200u8 {
+            for _ in 0..10 {
+                let value = random_nonzero_below(&mut rng, &bound);
+                assert!(value > 0 && value < bound);
+            }
+        }
+    }
+}
@@ -1,14 +1,24 @@
 //! Generic RSA implementation
+//!
+//! Public key operations use the variable time [`mod_exp`]. Every operation
+//! involving private key material (`d`, the primes or the CRT values) must use
+//! the constant-time [`CtMontgomery`] arithmetic instead.
 
-use num_traits::{FromPrimitive, One, Pow, Signed, Zero};
+use num_traits::{FromPrimitive, NumCast, One, Pow, Zero};
 use rand_core::CryptoRngCore;
 use zeroize::{Zeroize, Zeroizing};
 
+use super::modular::ct::{ct_add_mod, ct_eq, ct_sub_mod, CtMontgomery};
 use super::modular::exp::mod_exp;
+use super::modular::inverse::{gcd, mod_inverse};
+use super::modular::wide::Wide;
 use crate::traits::modular::UnsignedModularInt;
 
+use super::random::random_nonzero_below;
 use crate::errors::{Error, Result};
+use crate::primality::isqrt;
 use crate::traits::{PrivateKeyParts, PublicKeyParts};
+use crate::CrtValue;
 
 /// ⚠️ Raw RSA encryption of m with the public key. No padding is performed.
 ///
@@ -22,12 +32,12 @@ where
     K: PublicKeyParts<T>, // Public key trait with generic type T
     T: UnsignedModularInt,
 {
-    mod_exp(m, *key.e(), *key.n()) // Perform modular exponentiation
+    mod_exp(m, *key.e(), key.n()) // Perform modular exponentiation
 }
 
 /// ⚠️ Performs raw RSA decryption with no padding or error checking.
 ///
-/// Returns a plaintext `BigUint`. Performs RSA blinding if an `Rng` is passed.
+/// Returns a plaintext `T`. Performs RSA blinding if an `Rng` is passed.
 ///
 /// # ☢️️ WARNING: HAZARDOUS API ☢️
 ///
@@ -42,7 +52,7 @@ pub fn rsa_decrypt<T, R: CryptoRngCore + ?Sized>(
 where
     T: UnsignedModularInt,
 {
-    if c >= priv_key.n() {
+    if *c >= priv_key.n() {
         return Err(Error::Decryption);
     }
 
@@ -50,21 +60,85 @@ where
         return Err(Error::Decryption);
     }
 
-    let c = if let Some(ref mut rng) = rng {
-        let (blinded, unblinder) = blind(rng, priv_key, c);
+    let mut ir = None;
+    let c = Zeroizing::new(if let Some(ref mut rng) = rng {
+        let (blinded, unblinder) = blind(rng, priv_key, c)?;
+        ir = Some(unblinder);
         blinded
     } else {
         *c
-    };
+    });
 
     let dp = priv_key.dp();
     let dq = priv_key.dq();
-    todo!()
+    let qinv = priv_key.qinv();
+    let crt_values = priv_key.crt_values();
+
+    let m = Zeroizing::new(match (dp, dq, qinv, crt_values) {
+        (Some(dp), Some(dq), Some(qinv), Some(crt_values)) => {
+            // We have the precalculated values needed for the CRT.
+            decrypt_crt(priv_key.primes(), dp, dq, qinv, crt_values, &c)?
+        }
+        _ => {
+            let d = priv_key.d().ok_or(Error::InvalidArguments)?;
+            let n = CtMontgomery::new(priv_key.n()).ok_or(Error::InvalidModulus)?;
+            n.pow(*c, *d)
+        }
+    });
+
+    match ir {
+        Some(ref ir) => unblind(priv_key, &m, ir),
+        None => Ok(*m),
+    }
+}
+
+/// Applies the private exponent using the CRT values of the key.
+///
+/// `m = m2 + q·(qinv·(m1 - m2) mod p)` recombines the results modulo the first two
+/// primes, each additional prime is then folded in with its own coefficient.
+fn decrypt_crt<T>(
+    primes: &[T],
+    dp: &T,
+    dq: &T,
+    qinv: &T,
+    crt_values: &[CrtValue<T>],
+    c: &T,
+) -> Result<T>
+where
+    T: UnsignedModularInt,
+{
+    if primes.len() < 2 || primes.len() != crt_values.len() + 2 {
+        return Err(Error::InvalidPrime);
+    }
+    let p = CtMontgomery::new(primes[0]).ok_or(Error::InvalidPrime)?;
+    let q = CtMontgomery::new(primes[1]).ok_or(Error::InvalidPrime)?;
+
+    let m1 = Zeroizing::new(p.pow(*c, *dp));
+    let mut m2 = Zeroizing::new(q.pow(*c, *dq));
+
+    // h = qinv·(m1 - m2) mod p
+    let mut h = Zeroizing::new(ct_sub_mod(*m1, p.reduce(*m2), primes[0]));
+    *h = p.mul_mod(p.reduce(*qinv), *h);
+    let mut m = *m2 + *h * primes[1];
+
+    // Product of the primes folded in so far
+    let mut r = Zeroizing::new(primes[0] * primes[1]);
+    for (value, prime) in crt_values.iter().zip(&primes[2..]) {
+        let rm = CtMontgomery::new(*prime).ok_or(Error::InvalidPrime)?;
+        // h = coeff·(m_i - m) mod r_i, m += h·R
+        *m2 = rm.pow(*c, value.exp);
+        *h = ct_sub_mod(*m2, rm.reduce(m), *prime);
+        *h = rm.mul_mod(rm.reduce(value.coeff), *h);
+        m = m + *h * *r;
+        *r = *r * *prime;
+    }
+
+    Ok(m)
 }
 
 /// ⚠️ Performs raw RSA decryption with no padding.
 ///
-/// Returns a plaintext `BigUint`. Performs RSA blinding if an `Rng` is passed.  This will also
+/// Returns a plaintext `T`. Performs RSA blinding if an `Rng` is passed.  This will also
 /// check for errors in the CRT computation.
 ///
 /// # ☢️️ WARNING: HAZARDOUS API ☢️
@@ -72,12 +146,33 @@ where
 /// Use this function with great care! Raw RSA should never be used without an appropriate padding
 /// or signature scheme. See the [module-level documentation][crate::hazmat] for more information.
 #[inline]
-pub fn rsa_decrypt_and_check() {
-    todo!()
+pub fn rsa_decrypt_and_check<T, R: CryptoRngCore + ?Sized>(
+    priv_key: &impl PrivateKeyParts<T>,
+    rng: Option<&mut R>,
+    c: &T,
+) -> Result<T>
+where
+    T: UnsignedModularInt,
+{
+    let m = Zeroizing::new(rsa_decrypt(rng, priv_key, c)?);
+
+    // In order to defend against errors in the CRT computation, m^e is
+    // calculated, which should match the original ciphertext.
+    let n = CtMontgomery::new(priv_key.n()).ok_or(Error::InvalidModulus)?;
+    let check = Zeroizing::new(n.pow_public(*m, *priv_key.e()));
+    if !bool::from(ct_eq(*check, *c)) {
+        return Err(Error::Internal);
+    }
+
+    Ok(*m)
 }
 
 /// Returns the blinded c, along with the unblinding factor.
-fn blind<T, R: CryptoRngCore, K: PublicKeyParts<T>>(rng: &mut R, key: &K, c: &T) -> (T, T)
+fn blind<T, R: CryptoRngCore + ?Sized, K: PrivateKeyParts<T>>(
+    rng: &mut R,
+    key: &K,
+    c: &T,
+) -> Result<(T, Zeroizing<T>)>
 where
     T: UnsignedModularInt,
 {
@@ -85,35 +180,137 @@ where
     // Then the decryption operation performs (m^e * r^e)^d mod n
     // which equals mr mod n. The factor of r can then be removed
     // by multiplying by the multiplicative inverse of r.
+    let n = CtMontgomery::new(key.n()).ok_or(Error::InvalidModulus)?;
+    if key.primes().len() < 2 {
+        return Err(Error::NprimesTooSmall);
+    }
+
+    // r^-1 = r^(φ(n) - 1) mod n for r coprime to n
+    let mut phi = Zeroizing::new(T::one());
+    for prime in key.primes() {
+        *phi = *phi * (*prime - T::one());
+    }
+    let inverse_exp = Zeroizing::new(*phi - T::one());
+
+    let mut r = Zeroizing::new(T::zero());
+    let ir = loop {
+        *r = random_nonzero_below(rng, &key.n());
+        if let Some(ir) = n.invert(*r, *inverse_exp) {
+            break Zeroizing::new(ir);
+        }
+    };
 
-    let mut r: T;
-    let mut ir: Option<T>;
-    todo!()
+    let rpowe = Zeroizing::new(n.pow_public(*r, *key.e()));
+    Ok((n.mul_mod(*c, *rpowe), ir))
 }
 
 /// Given an m and and unblinding factor, unblind the m.
-fn unblind<T>(key: &impl PublicKeyParts<T>, m: &T, unblinder: &T) -> T
+fn unblind<T>(key: &impl PublicKeyParts<T>, m: &T, unblinder: &T) -> Result<T>
 where
     T: UnsignedModularInt,
 {
-    (*m * *unblinder) % *key.n()
+    let n = CtMontgomery::new(key.n()).ok_or(Error::InvalidModulus)?;
+    Ok(n.mul_mod(*m, *unblinder))
 }
 
 /// The following (deterministic) algorithm also recovers the prime factors `p` and `q` of a modulus `n`, given the
 /// public exponent `e` and private exponent `d` using the method described in
 /// [NIST 800-56B Appendix C.2](https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-56Br2.pdf).
-pub fn recover_primes<T>(n: &T, e: &T, d: &T) -> Result<(T, T)> {
+pub fn recover_primes<T>(n: &T, e: &T, d: &T) -> Result<(T, T)>
+where
+    T: UnsignedModularInt,
+{
     // Check precondition
-    todo!()
+    let one = T::one();
+    let min_e = <T as NumCast>::from(1u32 << 16).ok_or(Error::InvalidArguments)?;
+    if *e <= min_e || e.bits() > 256 || *n <= one {
+        return Err(Error::InvalidArguments);
+    }
+
+    // 1. Let a = (de – 1) × GCD(n – 1, de – 1).
+    // de and a are wider than `T`, so they are kept in three words.
+    let de_minus_one = Zeroizing::new(
+        Wide::from_word(*d)
+            .checked_mul(*e)
+            .and_then(|de| de.checked_sub(&Wide::from_word(one)))
+            .ok_or(Error::InvalidArguments)?,
+    );
+    let n_minus_one = *n - one;
+    let (_, de_mod) = de_minus_one.div_rem(n_minus_one);
+    let a = Zeroizing::new(
+        de_minus_one
+            .checked_mul(gcd(n_minus_one, de_mod))
+            .ok_or(Error::InvalidArguments)?,
+    );
+
+    // 2. Let m = floor(a /n) and r = a – m n, so that a = m n + r and 0 ≤ r < n.
+    let (m, r) = a.div_rem(*n);
+    let m = Zeroizing::new(m.to_word().ok_or(Error::InvalidArguments)?);
+    let r = Zeroizing::new(r);
+
+    // 3. Let b = ( (n – r)/(m + 1) ) + 1; if b is not an integer or b^2 ≤ 4n, then output an error indicator,
+    //    and exit without further processing.
+    let m_plus_one = m.checked_add(&one).ok_or(Error::InvalidArguments)?;
+    let modulus_check = Zeroizing::new((*n - *r) % m_plus_one);
+    if !modulus_check.is_zero() {
+        return Err(Error::InvalidArguments);
+    }
+    let b = Zeroizing::new(
+        ((*n - *r) / m_plus_one)
+            .checked_add(&one)
+            .ok_or(Error::InvalidArguments)?,
+    );
+
+    let four = <T as NumCast>::from(4u8).ok_or(Error::InvalidArguments)?;
+    let four_n = Wide::from_word(*n)
+        .checked_mul(four)
+        .ok_or(Error::InvalidArguments)?;
+    let b_squared = Zeroizing::new(
+        Wide::from_word(*b)
+            .checked_mul(*b)
+            .ok_or(Error::InvalidArguments)?,
+    );
+    if *b_squared <= four_n {
+        return Err(Error::InvalidArguments);
+    }
+    let b_squared_minus_four_n = Zeroizing::new(
+        b_squared
+            .checked_sub(&four_n)
+            .and_then(|diff| diff.to_word())
+            .ok_or(Error::InvalidArguments)?,
+    );
+
+    // 4. Let ϒ be the positive square root of b^2 – 4n; if ϒ is not an integer,
+    //    then output an error indicator, and exit without further processing.
+    let y = Zeroizing::new(isqrt(&*b_squared_minus_four_n));
+
+    let y_squared = Zeroizing::new(*y * *y);
+    let sqrt_is_whole_number = *y_squared == *b_squared_minus_four_n;
+    if !sqrt_is_whole_number {
+        return Err(Error::InvalidArguments);
+    }
+    // b and ϒ have the same parity, halve before adding to avoid overflow.
+    let p = (*b >> 1) + (*y >> 1) + (*b & *y & one);
+    let q = (*b - *y) >> 1;
+
+    Ok((p, q))
 }
 
 /// Compute the modulus of a key from its primes.
-pub(crate) fn compute_modulus<T>(primes: &[T]) -> T {
-    todo!()
+pub(crate) fn compute_modulus<T>(primes: &[T]) -> Result<T>
+where
+    T: UnsignedModularInt,
+{
+    primes.iter().try_fold(T::one(), |n, prime| {
+        n.checked_mul(prime).ok_or(Error::ModulusTooLarge)
+    })
 }
 
 /// Compute the private exponent from its primes (p and q) and public exponent
 /// This uses Euler's totient function
+///
+/// Runs the variable time [`mod_inverse`] on the secret totient. This is only
+/// meant for key setup, never for private key operations.
 #[inline]
 pub(crate) fn compute_private_exponent_euler_totient<T>(primes: &[T], exp: &T) -> Result<T>
 where
@@ -131,7 +328,12 @@ where
 
     // NOTE: `mod_inverse` checks if `exp` evenly divides `totient` and returns `None` if so.
     // This ensures that `exp` is not a factor of any `(prime - 1)`.
-    todo!()
+    if let Some(d) = mod_inverse(*exp, totient) {
+        Ok(d)
+    } else {
+        // `exp` evenly divides `totient`
+        Err(Error::InvalidPrime)
+    }
 }
 
 /// Compute the private exponent from its primes (p and q) and public exponent
@@ -142,6 +344,9 @@ where
 ///
 /// FIPS 186-4 **requires** the private exponent to be less than λ(n), which would
 /// make Euler's totiem unreliable.
+///
+/// Runs the variable time [`mod_inverse`] on the secret λ(n). This is only
+/// meant for key setup, never for private key operations.
 #[inline]
 pub(crate) fn compute_private_exponent_carmicheal<T>(p: &T, q: &T, exp: &T) -> Result<T>
 where
@@ -149,12 +354,178 @@ where
 {
     let p1 = *p - T::one();
     let q1 = *q - T::one();
-    todo!()
+    let lcm = p1 / gcd(p1, q1) * q1;
+
+    if let Some(d) = mod_inverse(*exp, lcm) {
+        Ok(d)
+    } else {
+        // `exp` evenly divides `lcm`
+        Err(Error::InvalidPrime)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use num_traits::FromPrimitive;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
 
     use super::*;
+    use crate::dummy_rng::DummyRng;
+
+    struct TestKey {
+        n: u64,
+        e: u64,
+        d: u64,
+        primes: [u64; 3],
+        nprimes: usize,
+        dp: u64,
+        dq: u64,
+        qinv: u64,
+        crt_values: [CrtValue<u64>; 1],
+        precomputed: bool,
+    }
+
+    impl PublicKeyParts<u64> for TestKey {
+        fn n(&self) -> u64 {
+            self.n
+        }
+
+        fn e(&self) -> &u64 {
+            &self.e
+        }
+    }
+
+    impl PrivateKeyParts<u64> for TestKey {
+        fn d(&self) -> Option<&u64> {
+            Some(&self.d)
+        }
+
+        fn primes(&self) -> &[u64] {
+            &self.primes[..self.nprimes]
+        }
+
+        fn dp(&self) -> Option<&u64> {
+            self.precomputed.then_some(&self.dp)
+        }
+
+        fn dq(&self) -> Option<&u64> {
+            self.precomputed.then_some(&self.dq)
+        }
+
+        fn qinv(&self) -> Option<&u64> {
+            self.precomputed.then_some(&self.qinv)
+        }
+
+        fn crt_values(&self) -> Option<&[CrtValue<u64>]> {
+            self.precomputed
+                .then_some(&self.crt_values[..self.nprimes - 2])
+        }
+    }
+
+    fn two_prime_key(precomputed: bool) -> TestKey {
+        TestKey {
+            n: 0xFFFF_FFEA_0000_0055,
+            e: 65537,
+            d: 108_506_947_230_999_203,
+            primes: [4_294_967_291, 4_294_967_279, 0],
+            nprimes: 2,
+            dp: 1_718_000_023,
+            dq: 2_021_164_927,
+            qinv: 3_937_053_350,
+            crt_values: [CrtValue { exp: 0, coeff: 0 }],
+            precomputed,
+        }
+    }
+
+    fn three_prime_key() -> TestKey {
+        TestKey {
+            n: 9_223_156_534_167_466_489,
+            e: 65537,
+            d: 919_295_635_813_068_533,
+            primes: [2_097_143, 2_097_133, 2_097_131],
+            nprimes: 3,
+            dp: 1_847_483,
+            dq: 120_989,
+            qinv: 1_468_000,
+            crt_values: [CrtValue {
+                exp: 1_436_923,
+                coeff: 1_135_946,
+            }],
+            precomputed: true,
+        }
+    }
+
+    #[test]
+    fn test_decrypt_without_crt() {
+        let key = two_prime_key(false);
+        let m = rsa_decrypt::<_, DummyRng>(None, &key, &0xFDCB_77A3_2DEA_5DF7).unwrap();
+        assert_eq!(m, 0x1234_5678_9ABC_DEF0);
+    }
+
+    #[test]
+    fn test_decrypt_crt() {
+        let key = two_prime_key(true);
+        let m = rsa_decrypt::<_, DummyRng>(None, &key, &0xFDCB_77A3_2DEA_5DF7).unwrap();
+        assert_eq!(m, 0x1234_5678_9ABC_DEF0);
+    }
+
+    #[test]
+    fn test_decrypt_multi_prime_crt() {
+        let key = three_prime_key();
+        let m = rsa_decrypt::<_, DummyRng>(None, &key, &0x1CFA_7164_7028_DE51).unwrap();
+        assert_eq!(m, 0x1234_5678_9ABC);
+    }
+
+    #[test]
+    fn test_decrypt_blinded() {
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        for key in [two_prime_key(false), two_prime_key(true), three_prime_key()] {
+            for m in [0u64, 1, 2, 0xDEAD_BEEF, key.n - 1] {
+                let c = rsa_encrypt(&key, m);
+                let decrypted = rsa_decrypt_and_check(&key, Some(&mut rng), &c).unwrap();
+                assert_eq!(decrypted, m);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decrypt_rejects_out_of_range() {
+        let key = two_prime_key(true);
+        assert_eq!(
+            rsa_decrypt::<_, DummyRng>(None, &key, &key.n),
+            Err(Error::Decryption)
+        );
+    }
+
+    #[test]
+    fn test_decrypt_and_check_detects_crt_fault() {
+        let mut key = two_prime_key(true);
+        key.dq ^= 2;
+        let c = rsa_encrypt(&key, 0x1234_5678);
+        assert_eq!(
+            rsa_decrypt_and_check::<_, DummyRng>(&key, None, &c),
+            Err(Error::Internal)
+        );
+    }
+
+    #[test]
+    fn test_recover_primes() {
+        let n = 274_369_763_417_138_269_028_364_561_594_783_285_337_u128;
+        let e = 65537;
+        let p = 18_356_656_716_024_858_401;
+        let q = 14_946_608_615_152_724_537;
+
+        // d computed modulo λ(n) and modulo φ(n)
+        for d in [
+            891_158_051_430_813_451_094_077_652_066_727_873,
+            164_457_747_780_878_627_659_688_311_963_062_435_073,
+        ] {
+            let (p1, q1) = recover_primes(&n, &e, &d).unwrap();
+            let (p1, q1) = if p1 < q1 { (q1, p1) } else { (p1, q1) };
+            assert_eq!((p1, q1), (p, q));
+        }
+
+        assert_eq!(recover_primes(&n, &3, &12345), Err(Error::InvalidArguments));
+        assert_eq!(recover_primes(&n, &e, &12345), Err(Error::InvalidArguments));
+    }
 }
@@ -0,0 +1,252 @@
+//! Runtime selected digests without a heap allocation.
+
+use const_oid::{AssociatedOid, ObjectIdentifier};
+use digest::{Digest, DynDigest};
+
+use crate::algorithms::pkcs1v15::pkcs1v15_generate_prefix;
+use crate::errors::Result;
+use crate::pkcs1v15::RsaSignatureAssociatedOid;
+use crate::Prefix;
+
+/// Runs the given closure with a fresh instance of a digest.
+///
+/// The runtime padding schemes store one of these where a `Box<dyn DynDigest>`
+/// would otherwise be needed. The digest only lives on the stack for the
+/// duration of the call.
+pub type DigestFn = fn(&mut dyn FnMut(&mut dyn DynDigest) -> Result<usize>) -> Result<usize>;
+
+/// [`DigestFn`] for the digest `D`.
+pub(crate) fn with_digest<D>(
+    f: &mut dyn FnMut(&mut dyn DynDigest) -> Result<usize>,
+) -> Result<usize>
+where
+    D: 'static + Digest + DynDigest,
+{
+    f(&mut D::new())
+}
+
+/// Evaluates `$body` with `$d` as the digest type of the hash algorithm `$alg`.
+macro_rules! with_hash_type {
+    ($alg:expr, $d:ident => $body:expr) => {
+        match $alg {
+            #[cfg(feature = "sha1")]
+            HashAlgorithm::Sha1 => {
+                type $d = sha1::Sha1;
+                $body
+            }
+            #[cfg(feature = "sha2")]
+            HashAlgorithm::Sha224 => {
+                type $d = sha2::Sha224;
+                $body
+            }
+            #[cfg(feature = "sha2")]
+            HashAlgorithm::Sha256 => {
+                type $d = sha2::Sha256;
+                $body
+            }
+            #[cfg(feature = "sha2")]
+            HashAlgorithm::Sha384 => {
+                type $d = sha2::Sha384;
+                $body
+            }
+            #[cfg(feature = "sha2")]
+            HashAlgorithm::Sha512 => {
+                type $d = sha2::Sha512;
+                $body
+            }
+            #[cfg(feature = "sha2")]
+            HashAlgorithm::Sha512_224 => {
+                type $d = sha2::Sha512_224;
+                $body
+            }
+            #[cfg(feature = "sha2")]
+            HashAlgorithm::Sha512_256 => {
+                type $d = sha2::Sha512_256;
+                $body
+            }
+            #[cfg(feature = "sha3")]
+            HashAlgorithm::Sha3_224 => {
+                type $d = sha3::Sha3_224;
+                $body
+            }
+            #[cfg(feature = "sha3")]
+            HashAlgorithm::Sha3_256 => {
+                type $d = sha3::Sha3_256;
+                $body
+            }
+            #[cfg(feature = "sha3")]
+            HashAlgorithm::Sha3_384 => {
+                type $d = sha3::Sha3_384;
+                $body
+            }
+            #[cfg(feature = "sha3")]
+            HashAlgorithm::Sha3_512 => {
+                type $d = sha3::Sha3_512;
+                $body
+            }
+        }
+    };
+}
+
+/// Hash algorithm selected at runtime.
+///
+/// The padding schemes can be created from one of these when the digest is
+/// only known from configuration or from an encoded `AlgorithmIdentifier`.
+/// Digests are instantiated on the stack whenever they are needed.
+///
+/// SHA-1, SHA-2 and SHA-3 require the `sha1`, `sha2` and `sha3` features
+/// respectively.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum HashAlgorithm {
+    /// SHA-1
+    #[cfg(feature = "sha1")]
+    Sha1,
+    /// SHA-224
+    #[cfg(feature = "sha2")]
+    Sha224,
+    /// SHA-256
+    #[cfg(feature = "sha2")]
+    Sha256,
+    /// SHA-384
+    #[cfg(feature = "sha2")]
+    Sha384,
+    /// SHA-512
+    #[cfg(feature = "sha2")]
+    Sha512,
+    /// SHA-512/224
+    #[cfg(feature = "sha2")]
+    Sha512_224,
+    /// SHA-512/256
+    #[cfg(feature = "sha2")]
+    Sha512_256,
+    /// SHA3-224
+    #[cfg(feature = "sha3")]
+    Sha3_224,
+    /// SHA3-256
+    #[cfg(feature = "sha3")]
+    Sha3_256,
+    /// SHA3-384
+    #[cfg(feature = "sha3")]
+    Sha3_384,
+    /// SHA3-512
+    #[cfg(feature = "sha3")]
+    Sha3_512,
+}
+
+impl HashAlgorithm {
+    /// All hash algorithms enabled in this build.
+    pub const ALL: &'static [Self] = &[
+        #[cfg(feature = "sha1")]
+        Self::Sha1,
+        #[cfg(feature = "sha2")]
+        Self::Sha224,
+        #[cfg(feature = "sha2")]
+        Self::Sha256,
+        #[cfg(feature = "sha2")]
+        Self::Sha384,
+        #[cfg(feature = "sha2")]
+        Self::Sha512,
+        #[cfg(feature = "sha2")]
+        Self::Sha512_224,
+        #[cfg(feature = "sha2")]
+        Self::Sha512_256,
+        #[cfg(feature = "sha3")]
+        Self::Sha3_224,
+        #[cfg(feature = "sha3")]
+        Self::Sha3_256,
+        #[cfg(feature = "sha3")]
+        Self::Sha3_384,
+        #[cfg(feature = "sha3")]
+        Self::Sha3_512,
+    ];
+
+    /// Looks up the hash algorithm with the given OID, as found in a digest
+    /// `AlgorithmIdentifier`.
+    pub fn from_oid(oid: ObjectIdentifier) -> Option<Self> {
+        Self::ALL.iter().copied().find(|alg| alg.oid() == oid)
+    }
+
+    /// Looks up the hash algorithm of the RSASSA-PKCS1-v1_5 signature
+    /// algorithm with the given OID, e.g. `sha256WithRSAEncryption`.
+    pub fn from_pkcs1v15_oid(oid: ObjectIdentifier) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|alg| alg.pkcs1v15_oid() == oid)
+    }
+
+    /// OID of the hash algorithm.
+    pub fn oid(self) -> ObjectIdentifier {
+        with_hash_type!(self, D => <D as AssociatedOid>::OID)
+    }
+
+    /// OID of the RSASSA-PKCS1-v1_5 signature algorithm with this hash
+    /// algorithm, e.g. `sha256WithRSAEncryption`.
+    pub fn pkcs1v15_oid(self) -> ObjectIdentifier {
+        with_hash_type!(self, D => <D as RsaSignatureAssociatedOid>::OID)
+    }
+
+    /// Length of the digest in bytes.
+    pub fn output_size(self) -> usize {
+        with_hash_type!(self, D => <D as Digest>::output_size())
+    }
+
+    /// Runs `f` with a fresh instance of the digest on the stack.
+    pub fn with_digest<R>(self, f: impl FnOnce(&mut dyn DynDigest) -> R) -> R {
+        with_hash_type!(self, D => f(&mut D::new()))
+    }
+
+    /// [`DigestFn`] for the digest, as stored by the runtime padding schemes.
+    pub fn digest_fn(self) -> DigestFn {
+        with_hash_type!(self, D => with_digest::<D> as DigestFn)
+    }
+
+    /// `DigestInfo` prefix of PKCS#1 v1.5 signatures made with the digest.
+    pub(crate) fn pkcs1v15_prefix(self) -> Prefix {
+        with_hash_type!(self, D => pkcs1v15_generate_prefix::<D>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_hash_algorithm() {
+        for alg in HashAlgorithm::ALL {
+            assert_eq!(HashAlgorithm::from_oid(alg.oid()), Some(*alg));
+            assert_eq!(
+                HashAlgorithm::from_pkcs1v15_oid(alg.pkcs1v15_oid()),
+                Some(*alg)
+            );
+            let len = alg.with_digest(|digest| {
+                digest.update(b"abc");
+                digest.output_size()
+            });
+            assert_eq!(len, alg.output_size());
+        }
+
+        let sha256 =
+            HashAlgorithm::from_oid(ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.1"));
+        assert_eq!(sha256, Some(HashAlgorithm::Sha256));
+        assert_eq!(
+            HashAlgorithm::from_oid(ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1")),
+            None
+        );
+
+        let mut output = [0u8; 32];
+        HashAlgorithm::Sha256.with_digest(|digest| {
+            digest.update(b"abc");
+            digest.finalize_into_reset(&mut output).unwrap();
+        });
+        assert_eq!(
+            output,
+            hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+
+        let len = (HashAlgorithm::Sha384.digest_fn())(&mut |digest| Ok(digest.output_size()));
+        assert_eq!(len, Ok(48));
+    }
+}
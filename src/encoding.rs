@@ -2,22 +2,81 @@
 //!
 //! Note: PKCS#1 support is achieved through a blanket impl of the
 //! `pkcs1` crate's traits for types which impl the `pkcs8` crate's traits.
+//!
+//! Keys are encoded into caller provided buffers by inherent methods such as
+//! [`RsaPublicKey::to_public_key_der`], which don't need `alloc`.
 
 use crate::{
+    algorithms::pad::be_bytes_to_uint,
+    errors::{Error, Result},
+    key::PrecomputedValues,
+    signature_algorithm::PssParams,
     traits::{PrivateKeyParts, PublicKeyParts, UnsignedModularInt},
-    RsaPrivateKey, RsaPublicKey,
+    CrtValue, HashAlgorithm, RsaPrivateKey, RsaPublicKey,
 };
 use core::convert::{TryFrom, TryInto};
 use pkcs8::{
-    der::{asn1::OctetStringRef, Encode},
+    der::{
+        self,
+        asn1::{OctetStringRef, UintRef},
+        Decode, Encode, EncodeValue, FixedTag, Header, Length, Reader, SliceReader, Tag, Writer,
+    },
     ObjectIdentifier,
 };
-use zeroize::Zeroizing;
+use zeroize::{Zeroize, Zeroizing};
 
 /// ObjectID for the RSA PSS keys
 pub const ID_RSASSA_PSS: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.10");
 
+/// ObjectID for the MGF1 mask generation function
+pub const ID_MGF_1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.8");
+
+/// Restrictions of an RSASSA-PSS key, decoded from the `RSASSA-PSS-params` in
+/// its `AlgorithmIdentifier` as described in [RFC4055 § 3.1].
+///
+/// A [`pss::VerifyingKey`](crate::pss::VerifyingKey) created with
+/// [`new_with_restriction`](crate::pss::VerifyingKey::new_with_restriction)
+/// enforces them.
+///
+/// [RFC4055 § 3.1]: https://datatracker.ietf.org/doc/html/rfc4055#section-3.1
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PssRestriction {
+    /// The only hash algorithm the key may be used with.
+    pub hash: HashAlgorithm,
+
+    /// The only hash algorithm MGF1 may be used with.
+    pub mgf_hash: HashAlgorithm,
+
+    /// Minimum salt length of signatures made with the key.
+    pub min_salt_len: usize,
+}
+
+/// Decodes the restrictions of a key from its `AlgorithmIdentifier`.
+///
+/// Returns `None` for `rsaEncryption` keys and for `id-RSASSA-PSS` keys
+/// without parameters, which are not restricted to particular parameters.
+pub fn pss_restriction(
+    algorithm: &pkcs8::AlgorithmIdentifierRef,
+) -> pkcs8::spki::Result<Option<PssRestriction>> {
+    verify_algorithm_id(algorithm)?;
+
+    match (algorithm.oid, algorithm.parameters) {
+        (ID_RSASSA_PSS, Some(parameters)) => {
+            let params =
+                PssParams::try_from(parameters).or(Err(pkcs8::spki::Error::KeyMalformed))?;
+            Ok(Some(PssRestriction {
+                hash: params.hash,
+                mgf_hash: params.mgf_hash,
+                min_salt_len: params.salt_len,
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
 /// Verify that the `AlgorithmIdentifier` for a key is correct.
+///
+/// `id-RSASSA-PSS` keys may carry `RSASSA-PSS-params`, which must be valid.
 pub(crate) fn verify_algorithm_id(
     algorithm: &pkcs8::AlgorithmIdentifierRef,
 ) -> pkcs8::spki::Result<()> {
@@ -28,8 +87,8 @@ pub(crate) fn verify_algorithm_id(
             }
         }
         ID_RSASSA_PSS => {
-            if algorithm.parameters.is_some() {
-                return Err(pkcs8::spki::Error::KeyMalformed);
+            if let Some(parameters) = algorithm.parameters {
+                PssParams::try_from(parameters).or(Err(pkcs8::spki::Error::KeyMalformed))?;
             }
         }
         _ => return Err(pkcs8::spki::Error::OidUnknown { oid: algorithm.oid }),
@@ -38,23 +97,90 @@ pub(crate) fn verify_algorithm_id(
     Ok(())
 }
 
-impl<T> TryFrom<pkcs8::PrivateKeyInfoRef<'_>> for RsaPrivateKey<T>
+/// Converts a DER `INTEGER` of a key to `T`.
+///
+/// Integers wider than `T` fail with [`ErrorKind::Overflow`], which tells a
+/// key that is too large for `T` apart from a malformed one.
+///
+/// [`ErrorKind::Overflow`]: pkcs8::der::ErrorKind::Overflow
+pub(crate) fn uint_from_der<T>(int: UintRef<'_>) -> pkcs8::der::Result<T>
+where
+    T: UnsignedModularInt,
+{
+    // `UintRef` has already stripped the leading zero byte of the encoding
+    be_bytes_to_uint(int.as_bytes()).or(Err(pkcs8::der::ErrorKind::Overflow.into()))
+}
+
+impl<T, const MAX_PRIMES: usize> TryFrom<pkcs8::PrivateKeyInfoRef<'_>>
+    for RsaPrivateKey<T, MAX_PRIMES>
 where
     T: UnsignedModularInt,
 {
     type Error = pkcs8::Error;
 
+    /// Two-prime and multi-prime keys are decoded without `alloc`, keys with
+    /// more than `MAX_PRIMES` primes fail with [`pkcs8::Error::KeyMalformed`].
     fn try_from(private_key_info: pkcs8::PrivateKeyInfoRef<'_>) -> pkcs8::Result<Self> {
         verify_algorithm_id(&private_key_info.algorithm)?;
 
-        let pkcs1_key = pkcs1::RsaPrivateKey::try_from(private_key_info.private_key)?;
+        // Every key has at least two primes, which `primes` must hold
+        if MAX_PRIMES < 2 {
+            return Err(pkcs8::Error::KeyMalformed);
+        }
+
+        let mut n = T::zero();
+        let mut e = T::zero();
+        let mut d = Zeroizing::new(T::zero());
+        let mut primes = Zeroizing::new([T::zero(); MAX_PRIMES]);
+        let mut nprimes = 0;
+        let mut precomputed = PrecomputedValues::<T, MAX_PRIMES>::empty();
+
+        // `pkcs1::RsaPrivateKey` can only hold `OtherPrimeInfos` with `alloc`,
+        // so the `RSAPrivateKey` is decoded here, straight into `T`.
+        let mut reader = SliceReader::new(private_key_info.private_key.as_bytes())?;
+        let version = reader.sequence(|reader| {
+            let version = pkcs1::Version::decode(reader)?;
+            n = uint_from_der(UintRef::decode(reader)?)?;
+            e = uint_from_der(UintRef::decode(reader)?)?;
+            *d = uint_from_der(UintRef::decode(reader)?)?;
+            primes[0] = uint_from_der(UintRef::decode(reader)?)?;
+            primes[1] = uint_from_der(UintRef::decode(reader)?)?;
+            let values = &mut precomputed.values;
+            values[0].exp = uint_from_der(UintRef::decode(reader)?)?;
+            values[1].exp = uint_from_der(UintRef::decode(reader)?)?;
+            values[1].coeff = uint_from_der(UintRef::decode(reader)?)?;
+            nprimes = 2;
+
+            if version.is_multi() {
+                // OtherPrimeInfos
+                reader.sequence(|reader| {
+                    while !reader.is_finished() {
+                        reader.sequence(|reader| {
+                            let prime = UintRef::decode(reader)?;
+                            let exponent = UintRef::decode(reader)?;
+                            let coefficient = UintRef::decode(reader)?;
+                            if nprimes < MAX_PRIMES {
+                                primes[nprimes] = uint_from_der(prime)?;
+                                values[nprimes].exp = uint_from_der(exponent)?;
+                                values[nprimes].coeff = uint_from_der(coefficient)?;
+                            }
+                            nprimes += 1;
+                            Ok(())
+                        })?;
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(version)
+        })?;
+        reader.finish(())?;
 
-        // Multi-prime RSA keys not currently supported
-        if pkcs1_key.version() != pkcs1::Version::TwoPrime {
-            return Err(pkcs1::Error::Version.into());
+        if version.is_multi() != (nprimes > 2) || nprimes > MAX_PRIMES {
+            return Err(pkcs8::Error::KeyMalformed);
         }
 
-        todo!()
+        RsaPrivateKey::from_components_with_precomputed(n, e, *d, &primes[..nprimes], precomputed)
+            .or(Err(pkcs8::Error::KeyMalformed))
     }
 }
 
@@ -72,15 +198,598 @@ where
                 .as_bytes()
                 .ok_or(pkcs8::spki::Error::KeyMalformed)?,
         )?;
-        todo!()
+        let n = uint_from_der(pkcs1_key.modulus)?;
+        let e = uint_from_der(pkcs1_key.public_exponent)?;
+        RsaPublicKey::new(n, e).or(Err(pkcs8::spki::Error::KeyMalformed))
+    }
+}
+
+impl<T> RsaPublicKey<T>
+where
+    T: UnsignedModularInt,
+{
+    /// Encodes the key as a PKCS#1 `RSAPublicKey` into `storage`. Returns the
+    /// used part of `storage`.
+    pub fn to_pkcs1_der<'a>(&self, storage: &'a mut [u8]) -> Result<&'a [u8]> {
+        encode_to_slice(&Pkcs1PublicKey(self), storage)
+    }
+
+    /// Encodes the key as a `SubjectPublicKeyInfo` into `storage`. Returns the
+    /// used part of `storage`.
+    pub fn to_public_key_der<'a>(&self, storage: &'a mut [u8]) -> Result<&'a [u8]> {
+        encode_to_slice(&KeyInfo::public(Pkcs1PublicKey(self)), storage)
+    }
+}
+
+impl<T, const MAX_PRIMES: usize> RsaPrivateKey<T, MAX_PRIMES>
+where
+    T: UnsignedModularInt,
+{
+    /// Encodes the key as a PKCS#1 `RSAPrivateKey` into `storage`. Returns
+    /// the used part of `storage`.
+    ///
+    /// Multi-prime keys include their `OtherPrimeInfos`. `storage` is zeroized
+    /// if the key doesn't fit.
+    pub fn to_pkcs1_der<'a>(&self, storage: &'a mut [u8]) -> Result<&'a [u8]> {
+        self.with_pkcs1_key(|key| encode_secret_to_slice(&key, storage))
+    }
+
+    /// Encodes the key as a PKCS#8 `PrivateKeyInfo` into `storage`. Returns
+    /// the used part of `storage`.
+    ///
+    /// `storage` is zeroized if the key doesn't fit.
+    pub fn to_pkcs8_der<'a>(&self, storage: &'a mut [u8]) -> Result<&'a [u8]> {
+        self.with_pkcs1_key(|key| encode_secret_to_slice(&KeyInfo::private(key), storage))
+    }
+
+    /// Encodes the public part of the key as a `SubjectPublicKeyInfo` into
+    /// `storage`. Returns the used part of `storage`.
+    pub fn to_public_key_der<'a>(&self, storage: &'a mut [u8]) -> Result<&'a [u8]> {
+        self.to_public_key().to_public_key_der(storage)
+    }
+
+    /// Runs `f` with the PKCS#1 `RSAPrivateKey` of the key. Keys without
+    /// precomputed values are copied to compute the CRT values PKCS#1 stores.
+    fn with_pkcs1_key<R>(
+        &self,
+        f: impl FnOnce(Pkcs1PrivateKey<'_, T, MAX_PRIMES>) -> Result<R>,
+    ) -> Result<R> {
+        match self.precomputed {
+            Some(_) => f(Pkcs1PrivateKey::new(self)?),
+            None => {
+                let mut key = self.clone();
+                key.precompute()?;
+                f(Pkcs1PrivateKey::new(&key)?)
+            }
+        }
+    }
+}
+
+/// Encodes `value` into `storage`, returning the used part of it.
+fn encode_to_slice<'a>(value: &impl Encode, storage: &'a mut [u8]) -> Result<&'a [u8]> {
+    if usize::try_from(value.encoded_len()?)? > storage.len() {
+        return Err(Error::OutputBufferTooSmall);
+    }
+    Ok(value.encode_to_slice(storage)?)
+}
+
+/// [`encode_to_slice`] for private keys, which zeroizes `storage` on failure.
+fn encode_secret_to_slice<'a>(value: &impl Encode, storage: &'a mut [u8]) -> Result<&'a [u8]> {
+    match encode_to_slice(value, storage).map(|der| der.len()) {
+        Ok(len) => Ok(&storage[..len]),
+        Err(err) => {
+            storage.zeroize();
+            Err(err)
+        }
+    }
+}
+
+/// Calls `f` with `value` as a DER `INTEGER`. The big-endian bytes of `value`
+/// are zeroized afterwards.
+fn with_uint_ref<T, R>(value: &T, f: impl FnOnce(UintRef<'_>) -> der::Result<R>) -> der::Result<R>
+where
+    T: UnsignedModularInt,
+{
+    let mut bytes = value.to_be_bytes();
+    let result = UintRef::new(bytes.as_ref()).and_then(f);
+    bytes.as_mut().zeroize();
+    result
+}
+
+/// Encoded length of `values` as DER `INTEGER`s.
+fn uints_len<'a, T>(values: impl IntoIterator<Item = &'a T>) -> der::Result<Length>
+where
+    T: UnsignedModularInt + 'a,
+{
+    values.into_iter().try_fold(Length::ZERO, |len, value| {
+        len + with_uint_ref(value, |int| int.encoded_len())?
+    })
+}
+
+/// Encodes `values` as DER `INTEGER`s.
+fn encode_uints<'a, T>(
+    values: impl IntoIterator<Item = &'a T>,
+    writer: &mut impl Writer,
+) -> der::Result<()>
+where
+    T: UnsignedModularInt + 'a,
+{
+    values
+        .into_iter()
+        .try_for_each(|value| with_uint_ref(value, |int| int.encode(writer)))
+}
+
+/// PKCS#1 `RSAPublicKey`.
+struct Pkcs1PublicKey<'a, T: UnsignedModularInt>(&'a RsaPublicKey<T>);
+
+impl<T: UnsignedModularInt> EncodeValue for Pkcs1PublicKey<'_, T> {
+    fn value_len(&self) -> der::Result<Length> {
+        uints_len([&self.0.n(), self.0.e()])
+    }
+
+    fn encode_value(&self, writer: &mut impl Writer) -> der::Result<()> {
+        encode_uints([&self.0.n(), self.0.e()], writer)
+    }
+}
+
+impl<T: UnsignedModularInt> FixedTag for Pkcs1PublicKey<'_, T> {
+    const TAG: Tag = Tag::Sequence;
+}
+
+/// PKCS#1 `RSAPrivateKey` of a key with precomputed CRT values.
+struct Pkcs1PrivateKey<'a, T: UnsignedModularInt, const MAX_PRIMES: usize> {
+    key: &'a RsaPrivateKey<T, MAX_PRIMES>,
+    n: T,
+    d: Zeroizing<T>,
+    precomputed: &'a PrecomputedValues<T, MAX_PRIMES>,
+}
+
+impl<'a, T: UnsignedModularInt, const MAX_PRIMES: usize> Pkcs1PrivateKey<'a, T, MAX_PRIMES> {
+    fn new(key: &'a RsaPrivateKey<T, MAX_PRIMES>) -> Result<Self> {
+        Ok(Self {
+            key,
+            n: key.n(),
+            d: Zeroizing::new(key.private_exponent()?),
+            precomputed: key.precomputed.as_ref().ok_or(Error::Internal)?,
+        })
+    }
+
+    fn version(&self) -> pkcs1::Version {
+        match self.key.nprimes {
+            2 => pkcs1::Version::TwoPrime,
+            _ => pkcs1::Version::Multi,
+        }
+    }
+
+    /// `n`, `e`, `d`, `p`, `q`, `dp`, `dq` and `qinv`, in encoding order.
+    fn uints(&self) -> [&T; 8] {
+        let primes = self.key.primes();
+        let values = &self.precomputed.values;
+        [
+            &self.n,
+            self.key.e(),
+            &*self.d,
+            &primes[0],
+            &primes[1],
+            &values[0].exp,
+            &values[1].exp,
+            &values[1].coeff,
+        ]
+    }
+
+    fn other_prime_infos(&self) -> Option<OtherPrimeInfos<'_, T>> {
+        let nprimes = self.key.nprimes;
+        (nprimes > 2).then(|| OtherPrimeInfos {
+            primes: &self.key.primes()[2..],
+            crt_values: &self.precomputed.values[2..nprimes],
+        })
+    }
+}
+
+impl<T: UnsignedModularInt, const MAX_PRIMES: usize> EncodeValue
+    for Pkcs1PrivateKey<'_, T, MAX_PRIMES>
+{
+    fn value_len(&self) -> der::Result<Length> {
+        self.version().encoded_len()?
+            + uints_len(self.uints())?
+            + self.other_prime_infos().encoded_len()?
+    }
+
+    fn encode_value(&self, writer: &mut impl Writer) -> der::Result<()> {
+        self.version().encode(writer)?;
+        encode_uints(self.uints(), writer)?;
+        self.other_prime_infos().encode(writer)
+    }
+}
+
+impl<T: UnsignedModularInt, const MAX_PRIMES: usize> FixedTag
+    for Pkcs1PrivateKey<'_, T, MAX_PRIMES>
+{
+    const TAG: Tag = Tag::Sequence;
+}
+
+/// `OtherPrimeInfos` of a multi-prime key: the primes after `p` and `q`,
+/// each with its CRT exponent and coefficient.
+struct OtherPrimeInfos<'a, T: UnsignedModularInt> {
+    primes: &'a [T],
+    crt_values: &'a [CrtValue<T>],
+}
+
+impl<T: UnsignedModularInt> OtherPrimeInfos<'_, T> {
+    /// Length of the `OtherPrimeInfo` of each prime.
+    fn info_lens(&self) -> impl Iterator<Item = der::Result<Length>> + '_ {
+        self.primes
+            .iter()
+            .zip(self.crt_values)
+            .map(|(prime, value)| uints_len([prime, &value.exp, &value.coeff]))
+    }
+}
+
+impl<T: UnsignedModularInt> EncodeValue for OtherPrimeInfos<'_, T> {
+    fn value_len(&self) -> der::Result<Length> {
+        self.info_lens().try_fold(Length::ZERO, |len, info_len| {
+            let info_len = info_len?;
+            len + Header::new(Tag::Sequence, info_len)?.encoded_len()? + info_len
+        })
+    }
+
+    fn encode_value(&self, writer: &mut impl Writer) -> der::Result<()> {
+        for ((prime, value), info_len) in self
+            .primes
+            .iter()
+            .zip(self.crt_values)
+            .zip(self.info_lens())
+        {
+            Header::new(Tag::Sequence, info_len?)?.encode(writer)?;
+            encode_uints([prime, &value.exp, &value.coeff], writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: UnsignedModularInt> FixedTag for OtherPrimeInfos<'_, T> {
+    const TAG: Tag = Tag::Sequence;
+}
+
+/// `SubjectPublicKeyInfo` or PKCS#8 `PrivateKeyInfo` of an `rsaEncryption`
+/// key. The PKCS#1 key is encoded in place rather than into a separate buffer
+/// first.
+struct KeyInfo<K> {
+    key: K,
+    private: bool,
+}
+
+impl<K: Encode> KeyInfo<K> {
+    fn public(key: K) -> Self {
+        Self {
+            key,
+            private: false,
+        }
+    }
+
+    fn private(key: K) -> Self {
+        Self { key, private: true }
+    }
+
+    /// Header of the `BIT STRING` or `OCTET STRING` holding the key.
+    fn key_header(&self) -> der::Result<Header> {
+        match self.private {
+            true => Header::new(Tag::OctetString, self.key.encoded_len()?),
+            // The leading byte of the BIT STRING counts its unused bits
+            false => Header::new(Tag::BitString, (self.key.encoded_len()? + 1u8)?),
+        }
+    }
+}
+
+impl<K: Encode> EncodeValue for KeyInfo<K> {
+    fn value_len(&self) -> der::Result<Length> {
+        let key_header = self.key_header()?;
+        let version_len = match self.private {
+            true => pkcs8::Version::V1.encoded_len()?,
+            false => Length::ZERO,
+        };
+        version_len
+            + pkcs1::ALGORITHM_ID.encoded_len()?
+            + key_header.encoded_len()?
+            + key_header.length
+    }
+
+    fn encode_value(&self, writer: &mut impl Writer) -> der::Result<()> {
+        if self.private {
+            pkcs8::Version::V1.encode(writer)?;
+        }
+        pkcs1::ALGORITHM_ID.encode(writer)?;
+        self.key_header()?.encode(writer)?;
+        if !self.private {
+            writer.write_byte(0)?;
+        }
+        self.key.encode(writer)
     }
 }
 
+impl<K> FixedTag for KeyInfo<K> {
+    const TAG: Tag = Tag::Sequence;
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use fixed_bigint::FixedUInt;
+    use hex_literal::hex;
+    use num_traits::{FromBytes, NumCast};
+    use pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey};
+    use pkcs8::der::Decode;
+    use pkcs8::{DecodePrivateKey, DecodePublicKey};
+
+    type U256 = FixedUInt<u32, 8>;
+    type U512 = FixedUInt<u32, 16>;
+    type U1024 = FixedUInt<u32, 32>;
+
+    const N: [u8; 64] = hex!(
+        "b2990f49c47dfa8cd400ae6a4d1b8a3b6a13642b23f28b003bfb97790ade9a4c"
+        "c82b8b2a81747ddec08b6296e53a08c331687ef25c4bf4936ba1c0e6041e9d15"
+    );
+
+    const PKCS1_PUBLIC_KEY: [u8; 74] = hex!(
+        "3048"
+        "024100b2990f49c47dfa8cd400ae6a4d1b8a3b6a13642b23f28b003bfb97790ade9a4c"
+        "c82b8b2a81747ddec08b6296e53a08c331687ef25c4bf4936ba1c0e6041e9d15"
+        "0203010001"
+    );
+
+    const SPKI_PUBLIC_KEY: [u8; 94] = hex!(
+        "305c300d06092a864886f70d0101010500034b00"
+        "3048"
+        "024100b2990f49c47dfa8cd400ae6a4d1b8a3b6a13642b23f28b003bfb97790ade9a4c"
+        "c82b8b2a81747ddec08b6296e53a08c331687ef25c4bf4936ba1c0e6041e9d15"
+        "0203010001"
+    );
+
     #[test]
-    #[ignore]
     fn test_try_from_publikey() {
-        todo!()
+        let expected = RsaPublicKey::new(
+            U512::from_be_bytes(&N),
+            <U512 as NumCast>::from(65537).unwrap(),
+        )
+        .unwrap();
+
+        let spki = pkcs8::SubjectPublicKeyInfoRef::from_der(&SPKI_PUBLIC_KEY).unwrap();
+        assert_eq!(RsaPublicKey::<U512>::try_from(spki), Ok(expected.clone()));
+        assert_eq!(
+            RsaPublicKey::<U512>::from_public_key_der(&SPKI_PUBLIC_KEY),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            RsaPublicKey::<U512>::from_pkcs1_der(&PKCS1_PUBLIC_KEY),
+            Ok(expected)
+        );
+
+        // A wider integer type holds the same key
+        let key = RsaPublicKey::<U1024>::from_pkcs1_der(&PKCS1_PUBLIC_KEY).unwrap();
+        let mut n = [0u8; 128];
+        n[64..].copy_from_slice(&N);
+        assert_eq!(key.n(), U1024::from_be_bytes(&n));
+        assert_eq!(key.e(), &<U1024 as NumCast>::from(65537).unwrap());
+
+        // The modulus does not fit in a narrower one
+        assert_eq!(
+            RsaPublicKey::<U256>::from_public_key_der(&SPKI_PUBLIC_KEY),
+            Err(pkcs8::spki::Error::Asn1(
+                pkcs8::der::ErrorKind::Overflow.into()
+            ))
+        );
+
+        // An even modulus is not a valid key
+        let mut even = PKCS1_PUBLIC_KEY;
+        even[68] ^= 1;
+        assert!(RsaPublicKey::<U512>::from_pkcs1_der(&even).is_err());
+    }
+
+    #[test]
+    fn test_uint_from_der() {
+        // The leading zero byte of a positive integer doesn't count towards `T`
+        let int = UintRef::from_der(&hex!("020900febff20686734721")).unwrap();
+        assert_eq!(uint_from_der::<u64>(int), Ok(0xfebf_f206_8673_4721));
+        let int = UintRef::from_der(&hex!("020100")).unwrap();
+        assert_eq!(uint_from_der::<u64>(int), Ok(0));
+
+        let int = UintRef::from_der(&hex!("020901febff20686734721")).unwrap();
+        assert_eq!(
+            uint_from_der::<u64>(int).map_err(|err| err.kind()),
+            Err(pkcs8::der::ErrorKind::Overflow)
+        );
+
+        // A redundant leading zero is not valid DER
+        assert!(UintRef::from_der(&hex!("020a0000febff20686734721")).is_err());
+        let noncanonical = hex!(
+            "305d300d06092a864886f70d0101010500034c00"
+            "3049"
+            "024100b2990f49c47dfa8cd400ae6a4d1b8a3b6a13642b23f28b003bfb97790ade9a4c"
+            "c82b8b2a81747ddec08b6296e53a08c331687ef25c4bf4936ba1c0e6041e9d15"
+            "020400010001"
+        );
+        assert!(RsaPublicKey::<U512>::from_public_key_der(&noncanonical).is_err());
+    }
+
+    #[test]
+    fn test_verify_algorithm_id() {
+        // `rsaEncryption` requires NULL parameters
+        let mut params = SPKI_PUBLIC_KEY;
+        params[15] = Tag::OctetString.into();
+        assert_eq!(
+            RsaPublicKey::<U512>::from_public_key_der(&params),
+            Err(pkcs8::spki::Error::KeyMalformed)
+        );
+        let mut params = PKCS8_PRIVATE_KEY;
+        params[18] = Tag::OctetString.into();
+        assert!(RsaPrivateKey::<u128>::from_pkcs8_der(&params).is_err());
+
+        // sha256WithRSAEncryption is not a key algorithm
+        let mut oid = SPKI_PUBLIC_KEY;
+        oid[14] = 11;
+        assert_eq!(
+            RsaPublicKey::<U512>::from_public_key_der(&oid),
+            Err(pkcs8::spki::Error::OidUnknown {
+                oid: ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11")
+            })
+        );
+        let mut oid = PKCS8_PRIVATE_KEY;
+        oid[17] = 11;
+        assert!(RsaPrivateKey::<u128>::from_pkcs8_der(&oid).is_err());
+    }
+
+    /// PKCS#1 `RSAPrivateKey` of the two-prime key from `key::tests`
+    const PKCS1_PRIVATE_KEY: [u8; 101] = hex!(
+        "3063020100"
+        "021100ce69b1bef3c9b78c12cfb06d72dec059"
+        "0203010001"
+        "021000aba17bf4501a51d6b56cac560977c1"
+        "020900febff20686734721"
+        "020900cf6d05584ef8aa39"
+        "02082b8922137b66b801"
+        "020900af2bd0a1bd3f7ef1"
+        "020900822ce642ab3e847a"
+    );
+
+    /// The same key as a PKCS#8 `PrivateKeyInfo`
+    const PKCS8_PRIVATE_KEY: [u8; 123] = hex!(
+        "3079020100300d06092a864886f70d01010105000465"
+        "3063020100"
+        "021100ce69b1bef3c9b78c12cfb06d72dec059"
+        "0203010001"
+        "021000aba17bf4501a51d6b56cac560977c1"
+        "020900febff20686734721"
+        "020900cf6d05584ef8aa39"
+        "02082b8922137b66b801"
+        "020900af2bd0a1bd3f7ef1"
+        "020900822ce642ab3e847a"
+    );
+
+    /// Three-prime PKCS#8 key, with the third prime in `OtherPrimeInfos`
+    const PKCS8_MULTI_PRIME_KEY: [u8; 96] = hex!(
+        "305e020100300d06092a864886f70d0101010500044a"
+        "3048020101"
+        "02087fff3c005edff1f9"
+        "0203010001"
+        "02086cc16282e8d46211"
+        "02031ffff7"
+        "02031fffed"
+        "02031c30bb"
+        "020301d89d"
+        "0203166660"
+        "3011300f02031fffeb020315ecfb020311554a"
+    );
+
+    #[test]
+    fn test_try_from_private_key() {
+        let p = 18_356_656_716_024_858_401_u128;
+        let q = 14_946_608_615_152_724_537;
+        let expected = RsaPrivateKey::<u128>::from_p_q(p, q, 65537).unwrap();
+
+        let key = RsaPrivateKey::<u128>::from_pkcs8_der(&PKCS8_PRIVATE_KEY).unwrap();
+        assert_eq!(key, expected);
+        assert_eq!(key.dp(), Some(&3_137_076_082_510_313_473));
+        assert_eq!(key.dq(), Some(&12_622_411_773_719_314_161));
+        assert_eq!(key.qinv(), Some(&9_380_125_297_921_131_642));
+
+        let key = RsaPrivateKey::<u128>::from_pkcs1_der(&PKCS1_PRIVATE_KEY).unwrap();
+        assert_eq!(key, expected);
+
+        // A stored CRT value that doesn't match the key
+        let mut bad_dp = PKCS8_PRIVATE_KEY;
+        bad_dp[100] ^= 2;
+        assert_eq!(
+            RsaPrivateKey::<u128>::from_pkcs8_der(&bad_dp),
+            Err(pkcs8::Error::KeyMalformed)
+        );
+
+        let mut bad_qinv = PKCS8_PRIVATE_KEY;
+        bad_qinv[122] ^= 1;
+        assert_eq!(
+            RsaPrivateKey::<u128>::from_pkcs8_der(&bad_qinv),
+            Err(pkcs8::Error::KeyMalformed)
+        );
+
+        // A key type that can't hold two primes
+        assert_eq!(
+            RsaPrivateKey::<u128, 1>::from_pkcs8_der(&PKCS8_PRIVATE_KEY),
+            Err(pkcs8::Error::KeyMalformed)
+        );
+
+        // Integers wider than `T`
+        match RsaPrivateKey::<u64>::from_pkcs8_der(&PKCS8_PRIVATE_KEY) {
+            Err(pkcs8::Error::Asn1(err)) => assert_eq!(err.kind(), pkcs8::der::ErrorKind::Overflow),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_try_from_multi_prime_private_key() {
+        let key = RsaPrivateKey::<u64, 3>::from_pkcs8_der(&PKCS8_MULTI_PRIME_KEY).unwrap();
+        assert_eq!(key.n(), 9_223_156_534_167_466_489);
+        assert_eq!(key.d(), Some(&7_836_653_140_992_877_073));
+        assert_eq!(key.primes(), &[2_097_143, 2_097_133, 2_097_131]);
+
+        let crt_values = key.crt_values().unwrap();
+        assert_eq!(crt_values.len(), 1);
+        assert_eq!(crt_values[0].exp, 1_436_923);
+        assert_eq!(crt_values[0].coeff, 1_135_946);
+
+        let expected =
+            RsaPrivateKey::<u64, 3>::from_primes(&[2_097_143, 2_097_133, 2_097_131], 65537)
+                .unwrap();
+        assert_eq!(key, expected);
+
+        // More primes than the key can hold
+        assert_eq!(
+            RsaPrivateKey::<u64>::from_pkcs8_der(&PKCS8_MULTI_PRIME_KEY),
+            Err(pkcs8::Error::KeyMalformed)
+        );
+    }
+
+    #[test]
+    fn test_encode_public_key() {
+        let key = RsaPublicKey::<U512>::from_pkcs1_der(&PKCS1_PUBLIC_KEY).unwrap();
+        let mut storage = [0u8; 128];
+        assert_eq!(key.to_pkcs1_der(&mut storage), Ok(&PKCS1_PUBLIC_KEY[..]));
+        assert_eq!(
+            key.to_public_key_der(&mut storage),
+            Ok(&SPKI_PUBLIC_KEY[..])
+        );
+
+        assert_eq!(
+            key.to_public_key_der(&mut storage[..93]),
+            Err(Error::OutputBufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_encode_private_key() {
+        let mut key = RsaPrivateKey::<u128>::from_pkcs8_der(&PKCS8_PRIVATE_KEY).unwrap();
+        let mut storage = [0u8; 128];
+        assert_eq!(key.to_pkcs1_der(&mut storage), Ok(&PKCS1_PRIVATE_KEY[..]));
+        assert_eq!(key.to_pkcs8_der(&mut storage), Ok(&PKCS8_PRIVATE_KEY[..]));
+        assert_eq!(
+            key.to_public_key_der(&mut storage),
+            key.to_public_key().to_public_key_der(&mut [0u8; 128])
+        );
+
+        // The CRT values are computed when the key doesn't hold them
+        key.clear_precomputed();
+        assert_eq!(key.to_pkcs8_der(&mut storage), Ok(&PKCS8_PRIVATE_KEY[..]));
+
+        let key = RsaPrivateKey::<u64, 3>::from_pkcs8_der(&PKCS8_MULTI_PRIME_KEY).unwrap();
+        assert_eq!(
+            key.to_pkcs8_der(&mut storage),
+            Ok(&PKCS8_MULTI_PRIME_KEY[..])
+        );
+
+        // Nothing of the key is left behind in a buffer that is too small
+        let mut storage = [0xaa; 100];
+        assert_eq!(
+            key.to_pkcs8_der(&mut storage[..95]),
+            Err(Error::OutputBufferTooSmall)
+        );
+        assert!(storage[..95].iter().all(|byte| *byte == 0));
     }
 }
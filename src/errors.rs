@@ -66,6 +66,12 @@ pub enum Error {
 
     /// Digest buffer too small
     DigestBufferTooSmall,
+
+    /// Operation cancelled by a progress callback.
+    Cancelled,
+
+    /// PKCS#8 or DER encoding error.
+    Pkcs8(pkcs8::Error),
 }
 
 #[cfg(feature = "std")]
@@ -80,6 +86,24 @@ impl core::fmt::Display for Error {
     }
 }
 
+impl From<pkcs8::Error> for Error {
+    fn from(err: pkcs8::Error) -> Self {
+        Error::Pkcs8(err)
+    }
+}
+
+impl From<pkcs8::der::Error> for Error {
+    fn from(err: pkcs8::der::Error) -> Self {
+        Error::Pkcs8(err.into())
+    }
+}
+
+impl From<pkcs8::spki::Error> for Error {
+    fn from(err: pkcs8::spki::Error) -> Self {
+        Error::Pkcs8(err.into())
+    }
+}
+
 #[cfg(feature = "std")]
 impl From<Error> for signature::Error {
     fn from(err: Error) -> Self {
@@ -1,11 +1,18 @@
 use core::hash::{Hash, Hasher};
+use core::ops::ControlFlow;
 use num_integer::Integer;
-use num_traits::{FromPrimitive, One, ToPrimitive};
+use num_traits::{FromPrimitive, NumCast, One, ToPrimitive};
 use rand_core::CryptoRngCore;
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use subtle::Choice;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 use crate::traits::UnsignedModularInt;
 
+use crate::algorithms::generate::{generate_multi_prime_key_with_exp, KeyGenProgress};
+use crate::algorithms::modular::ct::{ct_eq, ct_invert_prime, ct_lt, ct_reduce, CtMontgomery};
+use crate::algorithms::modular::mul::mod_mul;
+use crate::algorithms::pad::be_bytes_to_uint;
+use crate::algorithms::pkcs1v15::pkcs1v15_implicit_rejection_secret;
 use crate::algorithms::rsa::{
     compute_modulus, compute_private_exponent_carmicheal, compute_private_exponent_euler_totient,
     recover_primes,
@@ -13,6 +20,8 @@ use crate::algorithms::rsa::{
 
 use crate::dummy_rng::DummyRng;
 use crate::errors::{Error, Result};
+use crate::pkcs1v15;
+use crate::primality::baillie_psw;
 use crate::traits::{PaddingScheme, PrivateKeyParts, PublicKeyParts, SignatureScheme};
 use crate::CrtValue;
 
@@ -32,83 +41,92 @@ where
 }
 
 /// Represents a whole RSA key, public and private parts.
+///
+/// `MAX_PRIMES` is the largest number of prime factors the key can hold. It
+/// defaults to two, which is all that is needed outside of multi-prime RSA,
+/// and sizes the storage for the primes and the CRT values.
 #[derive(Debug, Clone)]
-pub struct RsaPrivateKey<T>
+pub struct RsaPrivateKey<T, const MAX_PRIMES: usize = 2>
 where
     T: UnsignedModularInt,
 {
-    /// Public components of the private key.
-    pubkey_components: RsaPublicKey<T>,
-    /// Private exponent
-    pub(crate) d: T,
-    /// Prime factors of N, contains >= 2 elements.
-    pub(crate) primes: [T; 4],
+    /// Public exponent. The modulus is the product of the primes.
+    e: T,
+    /// Private exponent. `None` for CRT-only keys, which only keep the
+    /// precomputed CRT values.
+    pub(crate) d: Option<T>,
+    /// Prime factors of N. Only the first `nprimes` entries are in use.
+    pub(crate) primes: [T; MAX_PRIMES],
+    /// Number of prime factors, at least 2.
+    pub(crate) nprimes: usize,
     /// precomputed values to speed up private operations
-    pub(crate) precomputed: Option<PrecomputedValues<T>>,
+    pub(crate) precomputed: Option<PrecomputedValues<T, MAX_PRIMES>>,
 }
 
-impl<T: UnsignedModularInt> Eq for RsaPrivateKey<T> {}
-impl<T: UnsignedModularInt> PartialEq for RsaPrivateKey<T> {
+impl<T: UnsignedModularInt, const MAX_PRIMES: usize> Eq for RsaPrivateKey<T, MAX_PRIMES> {}
+impl<T: UnsignedModularInt, const MAX_PRIMES: usize> PartialEq for RsaPrivateKey<T, MAX_PRIMES> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
-        self.pubkey_components == other.pubkey_components
-            && self.d == other.d
-            && self.primes == other.primes
-    }
-}
-
-impl<T: UnsignedModularInt> AsRef<RsaPublicKey<T>> for RsaPrivateKey<T> {
-    fn as_ref(&self) -> &RsaPublicKey<T> {
-        &self.pubkey_components
+        self.e == other.e && self.d == other.d && self.primes() == other.primes()
     }
 }
 
-impl<T: UnsignedModularInt> Drop for RsaPrivateKey<T> {
+impl<T: UnsignedModularInt, const MAX_PRIMES: usize> Drop for RsaPrivateKey<T, MAX_PRIMES> {
     fn drop(&mut self) {
         self.d.zeroize();
-        self.primes.zeroize();
+        self.primes[..self.nprimes].zeroize();
         self.precomputed.zeroize();
     }
 }
 
-impl<T: UnsignedModularInt> ZeroizeOnDrop for RsaPrivateKey<T> {}
+impl<T: UnsignedModularInt, const MAX_PRIMES: usize> ZeroizeOnDrop
+    for RsaPrivateKey<T, MAX_PRIMES>
+{
+}
 
 #[derive(Debug, Clone)]
-pub(crate) struct PrecomputedValues<T: Zeroize + UnsignedModularInt> {
-    /// D mod (P-1)
-    pub(crate) dp: T,
-    /// D mod (Q-1)
-    pub(crate) dq: T,
-    /// Q^-1 mod P
-    pub(crate) qinv: T,
-
-    /// CRTValues is used for the 3rd and subsequent primes. Due to a
-    /// historical accident, the CRT for the first two primes is handled
-    /// differently in PKCS#1 and interoperability is sufficiently
-    /// important that we mirror this.
-    pub(crate) crt_values: [CrtValue<T>; 3],
+pub(crate) struct PrecomputedValues<T: Zeroize + UnsignedModularInt, const MAX_PRIMES: usize> {
+    /// CRT values, one per prime.
+    ///
+    /// Due to a historical accident, the CRT for the first two primes is
+    /// handled differently in PKCS#1 and interoperability is sufficiently
+    /// important that we mirror this: the first entry only holds D mod (P-1)
+    /// in `exp`, the second holds D mod (Q-1) in `exp` and Q^-1 mod P in
+    /// `coeff`. Regular CRT values start with the 3rd prime.
+    pub(crate) values: [CrtValue<T>; MAX_PRIMES],
 }
 
-impl<T: Zeroize + UnsignedModularInt> Zeroize for PrecomputedValues<T> {
+impl<T: UnsignedModularInt, const MAX_PRIMES: usize> PrecomputedValues<T, MAX_PRIMES> {
+    /// All-zero values, to be filled in.
+    pub(crate) fn empty() -> Self {
+        Self {
+            values: core::array::from_fn(|_| CrtValue {
+                exp: T::zero(),
+                coeff: T::zero(),
+            }),
+        }
+    }
+}
+
+impl<T: Zeroize + UnsignedModularInt, const MAX_PRIMES: usize> Zeroize
+    for PrecomputedValues<T, MAX_PRIMES>
+{
     fn zeroize(&mut self) {
-        self.dp.zeroize();
-        self.dq.zeroize();
-        self.qinv.zeroize();
-        for val in self.crt_values.iter_mut() {
+        for val in self.values.iter_mut() {
             val.zeroize();
         }
     }
 }
 
-impl<T: UnsignedModularInt> Drop for PrecomputedValues<T> {
+impl<T: UnsignedModularInt, const MAX_PRIMES: usize> Drop for PrecomputedValues<T, MAX_PRIMES> {
     fn drop(&mut self) {
         self.zeroize();
     }
 }
 
 impl<T: UnsignedModularInt> PublicKeyParts<T> for RsaPublicKey<T> {
-    fn n(&self) -> &T {
-        &self.n
+    fn n(&self) -> T {
+        self.n
     }
 
     fn e(&self) -> &T {
@@ -142,6 +160,21 @@ impl<T: UnsignedModularInt + Clone> RsaPublicKey<T> {
     ) -> Result<()> {
         scheme.verify(self, hashed, sig)
     }
+
+    /// Open a PKCS#1 v1.5 signature whose hash algorithm is not known in
+    /// advance, returning the hash OID and digest it was made over.
+    ///
+    /// The caller must still hash the message with that algorithm and compare
+    /// the result with the returned digest, which points into `storage`. See
+    /// [`pkcs1v15::open`] for details.
+    pub fn open_pkcs1v15<'a>(
+        &self,
+        sig: &[u8],
+        storage: &'a mut [u8],
+    ) -> Result<pkcs1v15::DigestInfo<'a>> {
+        let sig_uint = be_bytes_to_uint(sig).or(Err(Error::Verification))?;
+        pkcs1v15::open(self, &sig_uint, sig.len(), false, storage)
+    }
 }
 
 impl<T: UnsignedModularInt> RsaPublicKey<T> {
@@ -180,23 +213,76 @@ impl<T: UnsignedModularInt> RsaPublicKey<T> {
     }
 }
 
-impl<T: UnsignedModularInt> PublicKeyParts<T> for RsaPrivateKey<T> {
-    fn n(&self) -> &T {
-        &self.pubkey_components.n
+impl<T: UnsignedModularInt, const MAX_PRIMES: usize> PublicKeyParts<T>
+    for RsaPrivateKey<T, MAX_PRIMES>
+{
+    fn n(&self) -> T {
+        self.primes().iter().fold(T::one(), |n, prime| n * *prime)
     }
 
     fn e(&self) -> &T {
-        &self.pubkey_components.e
-    }
-    fn size(&self) -> usize {
-        todo!("Not yet implemented size")
+        &self.e
     }
 }
 
-impl<T: UnsignedModularInt> RsaPrivateKey<T> {
+impl<T: UnsignedModularInt, const MAX_PRIMES: usize> RsaPrivateKey<T, MAX_PRIMES> {
     /// Default exponent for RSA keys.
     const EXP: u64 = 65537;
 
+    /// Generate a new Rsa key pair of the given bit size using the passed in `rng`.
+    ///
+    /// `bit_size` must not exceed the width of `T`.
+    pub fn new<R: CryptoRngCore + ?Sized>(rng: &mut R, bit_size: usize) -> Result<Self> {
+        let exp = <T as NumCast>::from(Self::EXP).ok_or(Error::PublicExponentTooLarge)?;
+        Self::new_with_exp(rng, bit_size, exp)
+    }
+
+    /// Generate a new RSA key pair of the given bit size and the public exponent
+    /// using the passed in `rng`.
+    ///
+    /// Unless you have specific needs, you should use `RsaPrivateKey::new` instead.
+    pub fn new_with_exp<R: CryptoRngCore + ?Sized>(
+        rng: &mut R,
+        bit_size: usize,
+        exp: T,
+    ) -> Result<Self> {
+        Self::new_with_progress(rng, bit_size, exp, |_| ControlFlow::Continue(()))
+    }
+
+    /// Generate a new RSA key pair of the given bit size and the public exponent
+    /// using the passed in `rng`, reporting progress to `progress`.
+    ///
+    /// Generating large keys on small targets can take minutes. `progress` is
+    /// called for every prime candidate and every Miller-Rabin round, e.g. to feed
+    /// a watchdog. Returning [`ControlFlow::Break`] from it stops key generation
+    /// with [`Error::Cancelled`].
+    pub fn new_with_progress<R, F>(
+        rng: &mut R,
+        bit_size: usize,
+        exp: T,
+        mut progress: F,
+    ) -> Result<Self>
+    where
+        R: CryptoRngCore + ?Sized,
+        F: FnMut(KeyGenProgress) -> ControlFlow<()>,
+    {
+        let components = generate_multi_prime_key_with_exp(rng, 2, bit_size, exp, &mut progress)?;
+
+        // The primes were just tested, so skip the primality tests of
+        // `validate()`, which would run without reporting progress.
+        let mut k = RsaPrivateKey {
+            e: components.e,
+            d: Some(components.d),
+            primes: components.primes,
+            nprimes: components.nprimes,
+            precomputed: None,
+        };
+        check_public(&k)?;
+        k.precompute()?;
+
+        Ok(k)
+    }
+
     /// Constructs an RSA key pair from individual components:
     ///
     /// - `n`: RSA modulus
@@ -210,8 +296,36 @@ impl<T: UnsignedModularInt> RsaPrivateKey<T> {
     ///   is between 2^16 and 2^256.
     ///
     ///  [NIST SP 800-56B Revision 2]: https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-56Br2.pdf
-    pub fn from_components(n: T, e: T, d: T, mut primes: [T; 4]) -> Result<Self> {
-        todo!("")
+    pub fn from_components(n: T, e: T, d: T, primes: &[T]) -> Result<Self> {
+        let (primes, nprimes) = match primes.len() {
+            0 => {
+                // Recover `p` and `q` from `d`.
+                // See method in Appendix C.2: https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-56Br2.pdf
+                let (p, q) = recover_primes(&n, &e, &d)?;
+                (Self::store_primes(&[p, q])?, 2)
+            }
+            1 => return Err(Error::NprimesTooSmall),
+            nprimes => (Self::store_primes(primes)?, nprimes),
+        };
+
+        let mut k = RsaPrivateKey {
+            e,
+            d: Some(d),
+            primes,
+            nprimes,
+            precomputed: None,
+        };
+
+        // Always validate the key, to ensure precompute can't fail
+        k.validate()?;
+        if k.n() != n {
+            return Err(Error::InvalidModulus);
+        }
+
+        // precompute when possible, ignore error otherwise.
+        let _ = k.precompute();
+
+        Ok(k)
     }
 
     /// Constructs an RSA key pair from its two primes p and q.
@@ -221,37 +335,167 @@ impl<T: UnsignedModularInt> RsaPrivateKey<T> {
     /// Private exponent will be rebuilt using the method defined in
     /// [NIST 800-56B Section 6.2.1](https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-56Br2.pdf#page=47).
     pub fn from_p_q(p: T, q: T, public_exponent: T) -> Result<Self> {
-        if p == q {
+        if p == q || p <= T::one() || q <= T::one() {
             return Err(Error::InvalidPrime);
         }
-        todo!()
+
+        let n = compute_modulus(&[p, q])?;
+        let d = compute_private_exponent_carmicheal(&p, &q, &public_exponent)?;
+
+        Self::from_components(n, public_exponent, d, &[p, q])
+    }
+
+    /// Constructs a CRT-only RSA key pair from its public exponent and the CRT
+    /// parameters of its two primes, as exported by many secure elements:
+    ///
+    /// - `e`: public exponent
+    /// - `p`, `q`: the prime factors of `n`
+    /// - `dp`: `d mod (p-1)`
+    /// - `dq`: `d mod (q-1)`
+    /// - `qinv`: `q^-1 mod p`
+    ///
+    /// The key only stores these values. The modulus is computed from `p` and
+    /// `q` when needed, and the private exponent `d` is not derived, see
+    /// [`RsaPrivateKey::private_exponent`].
+    pub fn from_crt_components(e: T, p: T, q: T, dp: T, dq: T, qinv: T) -> Result<Self> {
+        Self::from_multi_prime_crt_components(e, &[p, q], &[dp, dq], &[qinv])
+    }
+
+    /// Constructs a CRT-only RSA key pair from its public exponent and the CRT
+    /// parameters of up to `MAX_PRIMES` primes:
+    ///
+    /// - `e`: public exponent
+    /// - `primes`: the prime factors of `n`
+    /// - `exps`: `d mod (prime-1)` for each prime
+    /// - `coeffs`: `q^-1 mod p` followed by the coefficient of each further
+    ///   prime, i.e. the inverse of the product of the primes before it
+    ///
+    /// Like [`RsaPrivateKey::from_crt_components`], the key only stores these
+    /// values.
+    pub fn from_multi_prime_crt_components(
+        e: T,
+        primes: &[T],
+        exps: &[T],
+        coeffs: &[T],
+    ) -> Result<Self> {
+        if primes.len() < 2 {
+            return Err(Error::NprimesTooSmall);
+        }
+        if exps.len() != primes.len() || coeffs.len() + 1 != primes.len() {
+            return Err(Error::InvalidArguments);
+        }
+        check_distinct_primes(primes)?;
+
+        let mut precomputed = PrecomputedValues::empty();
+        for (value, exp) in precomputed.values.iter_mut().zip(exps) {
+            value.exp = *exp;
+        }
+        for (value, coeff) in precomputed.values[1..].iter_mut().zip(coeffs) {
+            value.coeff = *coeff;
+        }
+
+        let k = RsaPrivateKey {
+            e,
+            d: None,
+            primes: Self::store_primes(primes)?,
+            nprimes: primes.len(),
+            precomputed: Some(precomputed),
+        };
+        k.validate()?;
+
+        Ok(k)
+    }
+
+    /// Constructs an RSA key pair from its components and the CRT values
+    /// stored along with them, e.g. in a PKCS#1 `RSAPrivateKey`.
+    ///
+    /// `precomputed` holds `d mod (prime-1)` for each prime, `qinv` and the
+    /// coefficient of each further prime. They are checked against `d` and
+    /// the primes.
+    pub(crate) fn from_components_with_precomputed(
+        n: T,
+        e: T,
+        d: T,
+        primes: &[T],
+        precomputed: PrecomputedValues<T, MAX_PRIMES>,
+    ) -> Result<Self> {
+        if primes.len() < 2 {
+            return Err(Error::NprimesTooSmall);
+        }
+
+        let mut k = RsaPrivateKey {
+            e,
+            d: Some(d),
+            primes: Self::store_primes(primes)?,
+            nprimes: primes.len(),
+            precomputed: None,
+        };
+        k.validate()?;
+        if k.n() != n {
+            return Err(Error::InvalidModulus);
+        }
+
+        // The stored values are secret, so they are checked in constant time,
+        // only failing after all of them have been checked
+        let values = &precomputed.values;
+        let mut valid = Choice::from(1);
+        for (value, prime) in values.iter().zip(primes) {
+            valid &= ct_eq(value.exp, ct_reduce(d, *prime - T::one()));
+        }
+        if !bool::from(valid) {
+            return Err(Error::InvalidExponent);
+        }
+
+        let (p, q) = (primes[0], primes[1]);
+        let mut valid = ct_is_coefficient(values[1].coeff, q, p)?;
+        let mut r = p * q;
+        for (value, prime) in values[2..].iter().zip(&primes[2..]) {
+            valid &= ct_is_coefficient(value.coeff, r, *prime)?;
+            r = r * *prime;
+        }
+        if !bool::from(valid) {
+            return Err(Error::InvalidCoefficient);
+        }
+
+        k.precomputed = Some(precomputed);
+
+        Ok(k)
     }
 
     /// Constructs an RSA key pair from its primes.
     ///
     /// This will rebuild the private exponent and the modulus.
-    pub fn from_primes(primes: [T; 4], public_exponent: T) -> Result<Self> {
+    pub fn from_primes(primes: &[T], public_exponent: T) -> Result<Self> {
         if primes.len() < 2 {
             return Err(Error::NprimesTooSmall);
         }
+        check_distinct_primes(primes)?;
 
-        // Makes sure that primes is pairwise unequal.
-        for (i, prime1) in primes.iter().enumerate() {
-            for prime2 in primes.iter().take(i) {
-                if prime1 == prime2 {
-                    return Err(Error::InvalidPrime);
-                }
-            }
+        let n = compute_modulus(primes)?;
+        let d = compute_private_exponent_euler_totient(primes, &public_exponent)?;
+
+        Self::from_components(n, public_exponent, d, primes)
+    }
+
+    /// Copies `primes` into the fixed-size storage of the key.
+    fn store_primes(primes: &[T]) -> Result<[T; MAX_PRIMES]> {
+        if primes.len() > MAX_PRIMES {
+            return Err(Error::InvalidArguments);
         }
-        todo!()
+        let mut storage = [T::zero(); MAX_PRIMES];
+        storage[..primes.len()].copy_from_slice(primes);
+        Ok(storage)
     }
 
-    /// Get the public key from the private key, cloning `n` and `e`.
+    /// Get the public key from the private key, computing `n` and cloning `e`.
     ///
     /// Generally this is not needed since `RsaPrivateKey` implements the `PublicKey` trait,
     /// but it can occasionally be useful to discard the private information entirely.
     pub fn to_public_key(&self) -> RsaPublicKey<T> {
-        self.pubkey_components.clone()
+        RsaPublicKey {
+            n: self.n(),
+            e: self.e,
+        }
     }
 
     /// Performs some calculations to speed up private key operations.
@@ -259,86 +503,273 @@ impl<T: UnsignedModularInt> RsaPrivateKey<T> {
         if self.precomputed.is_some() {
             return Ok(());
         }
-        todo!()
+
+        let nprimes = self.nprimes;
+        if nprimes < 2 {
+            return Err(Error::NprimesTooSmall);
+        }
+        // Only CRT-only keys lack `d`, and they are always precomputed.
+        let d = self.d.ok_or(Error::Internal)?;
+
+        let mut precomputed = PrecomputedValues::empty();
+        let values = &mut precomputed.values;
+        values[0].exp = d % (self.primes[0] - T::one());
+        values[1].exp = d % (self.primes[1] - T::one());
+        values[1].coeff =
+            ct_invert_prime(self.primes[1], self.primes[0]).ok_or(Error::InvalidPrime)?;
+
+        let mut r = self.primes[0] * self.primes[1];
+        for (value, prime) in values[2..nprimes].iter_mut().zip(&self.primes[2..nprimes]) {
+            *value = CrtValue {
+                exp: d % (*prime - T::one()),
+                coeff: ct_invert_prime(r, *prime).ok_or(Error::InvalidCoefficient)?,
+            };
+            r = r * *prime;
+        }
+
+        self.precomputed = Some(precomputed);
+
+        Ok(())
     }
 
     /// Clears precomputed values by setting to None
+    ///
+    /// CRT-only keys keep their values, as they can't be recomputed without `d`.
     pub fn clear_precomputed(&mut self) {
-        self.precomputed = None;
+        if self.d.is_some() {
+            self.precomputed = None;
+        }
+    }
+
+    /// Returns the private exponent `d`.
+    ///
+    /// CRT-only keys don't store `d`, for them it is derived from `e` and the
+    /// primes as `e^-1 mod lcm(p-1, q-1)`, or `e^-1 mod φ(n)` for more than two
+    /// primes, e.g. to encode the key in formats that require it. That
+    /// derivation is variable time, so it is not meant for private key
+    /// operations.
+    pub fn private_exponent(&self) -> Result<T> {
+        match self.d {
+            Some(d) => Ok(d),
+            None if self.nprimes == 2 => {
+                compute_private_exponent_carmicheal(&self.primes[0], &self.primes[1], self.e())
+            }
+            None => compute_private_exponent_euler_totient(self.primes(), self.e()),
+        }
+    }
+
+    /// Returns the digest of the private key that PKCS#1 v1.5 implicit
+    /// rejection derives its key from.
+    ///
+    /// This is the digest of `d`, as in draft-irtf-cfrg-rsa-guidance. CRT-only
+    /// keys hash their CRT values instead, so they return other synthetic
+    /// messages than the same key with `d`.
+    pub(crate) fn implicit_rejection_secret(&self) -> Result<Zeroizing<[u8; 32]>> {
+        let k = self.size();
+        match (&self.d, &self.precomputed) {
+            (Some(d), _) => Ok(pkcs1v15_implicit_rejection_secret([d], k)),
+            (None, Some(precomputed)) => {
+                let values = &precomputed.values[..self.nprimes];
+                let exps = values.iter().map(|value| &value.exp);
+                let coeffs = values[1..].iter().map(|value| &value.coeff);
+                Ok(pkcs1v15_implicit_rejection_secret(exps.chain(coeffs), k))
+            }
+            (None, None) => Err(Error::Internal),
+        }
     }
 
     /// Compute CRT coefficient: `(1/q) mod p`.
     pub fn crt_coefficient(&self) -> Option<T> {
-        todo!()
+        ct_invert_prime(self.primes[1], self.primes[0])
     }
 
     /// Performs basic sanity checks on the key.
     /// Returns `Ok(())` if everything is good, otherwise an appropriate error.
     pub fn validate(&self) -> Result<()> {
-        check_public(self)?;
+        if self.nprimes < 2 {
+            return Err(Error::NprimesTooSmall);
+        }
 
-        // Check that Πprimes == n.
+        // Check that Πprimes, which is n, fits before computing n.
         let mut m = T::one();
-        for prime in &self.primes {
+        for prime in self.primes() {
             // Any primes ≤ 1 will cause divide-by-zero panics later.
-            if *prime < T::one() {
+            if *prime <= T::one() {
                 return Err(Error::InvalidPrime);
             }
-            m = m * *prime;
-        }
-        if m != self.pubkey_components.n {
-            return Err(Error::InvalidModulus);
+            m = m.checked_mul(prime).ok_or(Error::InvalidModulus)?;
         }
 
+        check_public(self)?;
+
         // Check that de ≡ 1 mod p-1, for each prime.
         // This implies that e is coprime to each p-1 as e has a multiplicative
         // inverse. Therefore e is coprime to lcm(p-1,q-1,r-1,...) =
         // exponent(ℤ/nℤ). It also implies that a^de ≡ a mod p as a^(p-1) ≡ 1
         // mod p. Thus a^de ≡ a mod n for all a coprime to n, as required.
-        let mut de = *self.e();
-        de = de * self.d;
-        for prime in &self.primes {
-            let congruence: T = de % (*prime - T::one());
+        // CRT-only keys are checked with d mod (p-1) instead, which is all
+        // that is used of d.
+        for (i, prime) in self.primes().iter().enumerate() {
+            let p_minus_one = *prime - T::one();
+            let exp = match (&self.d, &self.precomputed) {
+                (Some(d), _) => *d,
+                (None, Some(precomputed)) if precomputed.values[i].exp < p_minus_one => {
+                    precomputed.values[i].exp
+                }
+                _ => return Err(Error::InvalidExponent),
+            };
+            let congruence: T = mod_mul(*self.e(), exp, p_minus_one);
             if !congruence.is_one() {
                 return Err(Error::InvalidExponent);
             }
         }
 
+        // CRT-only keys can't recompute the coefficients, so check the stored
+        // ones: qinv·q ≡ 1 mod p, and R·coeff ≡ 1 mod prime for each further
+        // prime, where R is the product of the primes before it.
+        if let (None, Some(precomputed)) = (&self.d, &self.precomputed) {
+            let (p, q) = (self.primes[0], self.primes[1]);
+            let qinv = precomputed.values[1].coeff;
+            if qinv >= p || !mod_mul(qinv, q, p).is_one() {
+                return Err(Error::InvalidCoefficient);
+            }
+            let mut r = p * q;
+            for (value, prime) in precomputed.values[2..].iter().zip(&self.primes()[2..]) {
+                if value.coeff >= *prime || !mod_mul(value.coeff, r, *prime).is_one() {
+                    return Err(Error::InvalidCoefficient);
+                }
+                r = r * *prime;
+            }
+        }
+
+        // Check that each prime actually is one.
+        for prime in self.primes() {
+            if !baillie_psw(prime) {
+                return Err(Error::InvalidPrime);
+            }
+        }
+
         Ok(())
     }
 }
 
-impl<T: UnsignedModularInt> PrivateKeyParts<T> for RsaPrivateKey<T> {
-    fn d(&self) -> &T {
-        &self.d
+impl<T: UnsignedModularInt + Clone, const MAX_PRIMES: usize> RsaPrivateKey<T, MAX_PRIMES> {
+    /// Decrypt the given message into `storage`, which must be at least as
+    /// long as the modulus. Returns the plaintext part of `storage`.
+    pub fn decrypt<'a, P: PaddingScheme<T>>(
+        &self,
+        padding: P,
+        ciphertext: &[u8],
+        storage: &'a mut [u8],
+    ) -> Result<&'a [u8]> {
+        padding.decrypt(Option::<&mut DummyRng>::None, self, ciphertext, storage)
+    }
+
+    /// Decrypt the given message into `storage`.
+    ///
+    /// Uses `rng` to blind the decryption process.
+    pub fn decrypt_blinded<'a, R: CryptoRngCore, P: PaddingScheme<T>>(
+        &self,
+        rng: &mut R,
+        padding: P,
+        ciphertext: &[u8],
+        storage: &'a mut [u8],
+    ) -> Result<&'a [u8]> {
+        padding.decrypt(Some(rng), self, ciphertext, storage)
+    }
+
+    /// Sign the given digest into `storage`, which must be at least as long
+    /// as the modulus. Returns the signature part of `storage`.
+    pub fn sign<'a, S: SignatureScheme<T>>(
+        &self,
+        padding: S,
+        digest_in: &[u8],
+        storage: &'a mut [u8],
+    ) -> Result<&'a [u8]> {
+        padding.sign(Option::<&mut DummyRng>::None, self, digest_in, storage)
+    }
+
+    /// Sign the given digest into `storage` using the provided `rng`, which
+    /// is used in the following ways depending on the [`SignatureScheme`]:
+    ///
+    /// - [`Pkcs1v15Sign`][`crate::Pkcs1v15Sign`] padding: uses the RNG
+    ///   to mask the private key operation with random blinding, which helps
+    ///   mitigate sidechannel attacks.
+    /// - [`Pss`][`crate::Pss`] always requires randomness for the salt, and
+    ///   also uses it for blinding if the scheme is blinded.
+    pub fn sign_with_rng<'a, R: CryptoRngCore, S: SignatureScheme<T>>(
+        &self,
+        rng: &mut R,
+        padding: S,
+        digest_in: &[u8],
+        storage: &'a mut [u8],
+    ) -> Result<&'a [u8]> {
+        padding.sign(Some(rng), self, digest_in, storage)
+    }
+}
+
+impl<T: UnsignedModularInt, const MAX_PRIMES: usize> PrivateKeyParts<T>
+    for RsaPrivateKey<T, MAX_PRIMES>
+{
+    fn d(&self) -> Option<&T> {
+        self.d.as_ref()
     }
 
     fn primes(&self) -> &[T] {
-        &self.primes
+        &self.primes[..self.nprimes]
     }
 
     fn dp(&self) -> Option<&T> {
-        self.precomputed.as_ref().map(|p| &p.dp)
+        self.precomputed.as_ref().map(|p| &p.values[0].exp)
     }
 
     fn dq(&self) -> Option<&T> {
-        self.precomputed.as_ref().map(|p| &p.dq)
+        self.precomputed.as_ref().map(|p| &p.values[1].exp)
     }
 
     fn qinv(&self) -> Option<&T> {
-        self.precomputed.as_ref().map(|p| &p.qinv)
+        self.precomputed.as_ref().map(|p| &p.values[1].coeff)
     }
 
     fn crt_values(&self) -> Option<&[CrtValue<T>]> {
         /* for some reason the standard self.precomputed.as_ref().map() doesn't work */
         if let Some(p) = &self.precomputed {
-            Some(p.crt_values.as_slice())
+            Some(&p.values[2..self.nprimes])
         } else {
             None
         }
     }
 }
 
+/// Returns whether `coeff < prime` and `coeff·r ≡ 1 mod prime`, in constant
+/// time.
+fn ct_is_coefficient<T>(coeff: T, r: T, prime: T) -> Result<Choice>
+where
+    T: UnsignedModularInt,
+{
+    let rm = CtMontgomery::new(prime).ok_or(Error::InvalidPrime)?;
+    let product = rm.mul_mod(rm.reduce(coeff), rm.reduce(r));
+    Ok(ct_lt(coeff, prime) & ct_eq(product, T::one()))
+}
+
+/// Check that the primes are greater than one and pairwise unequal.
+fn check_distinct_primes<T>(primes: &[T]) -> Result<()>
+where
+    T: UnsignedModularInt,
+{
+    for (i, prime1) in primes.iter().enumerate() {
+        if *prime1 <= T::one() {
+            return Err(Error::InvalidPrime);
+        }
+        for prime2 in primes.iter().take(i) {
+            if prime1 == prime2 {
+                return Err(Error::InvalidPrime);
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Check that the public key is well formed and has an exponent within acceptable bounds.
 #[inline]
 pub fn check_public<T>(public_key: &impl PublicKeyParts<T>) -> Result<()>
@@ -363,7 +794,7 @@ where
         .to_u64()
         .ok_or(Error::PublicExponentTooLarge)?;
 
-    if public_key.e() >= public_key.n() || public_key.n().is_even() {
+    if *public_key.e() >= public_key.n() || public_key.n().is_even() {
         return Err(Error::InvalidModulus);
     }
 
@@ -387,8 +818,10 @@ mod tests {
     use super::*;
     use crate::algorithms::rsa::{rsa_decrypt_and_check, rsa_encrypt};
 
+    use fixed_bigint::FixedUInt;
     use hex_literal::hex;
     use num_traits::{FromPrimitive, ToPrimitive};
+    use pkcs8::DecodePrivateKey;
     use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
 
     #[test]
@@ -397,45 +830,172 @@ mod tests {
         todo!()
     }
 
-    fn test_key_basics<T>(private_key: &RsaPrivateKey<T>)
+    fn test_key_basics<T, const MAX_PRIMES: usize>(private_key: &RsaPrivateKey<T, MAX_PRIMES>)
     where
         T: UnsignedModularInt,
     {
         private_key.validate().expect("invalid private key");
 
-        assert!(
-            private_key.d() < private_key.n(),
-            "private exponent too large"
-        );
+        if let Some(d) = private_key.d() {
+            assert!(*d < private_key.n(), "private exponent too large");
+        }
 
-        todo!()
+        let pub_key = private_key.to_public_key();
+        let m = <T as NumCast>::from(42u8).expect("invalid 42");
+        let c = rsa_encrypt(&pub_key, m);
+        let m2 = rsa_decrypt_and_check::<T, ChaCha8Rng>(private_key, None, &c)
+            .expect("unable to decrypt without blinding");
+        assert_eq!(m, m2);
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        let m3 = rsa_decrypt_and_check(private_key, Some(&mut rng), &c)
+            .expect("unable to decrypt with blinding");
+        assert_eq!(m, m3);
     }
 
     macro_rules! key_generation {
-        ($name:ident, $multi:expr, $size:expr) => {
+        ($(#[$attr:meta])* $name:ident, $ty:ty, $multi:expr, $size:expr) => {
             #[test]
-            #[ignore]
+            $(#[$attr])*
             fn $name() {
-                todo!()
+                let mut rng = ChaCha8Rng::from_seed([42; 32]);
+                let exp = <$ty as NumCast>::from(RsaPrivateKey::<$ty>::EXP)
+                    .expect("invalid static exponent");
+
+                for _ in 0..10 {
+                    let components = generate_multi_prime_key_with_exp::<_, _, _, $multi>(
+                        &mut rng,
+                        $multi,
+                        $size,
+                        exp,
+                        &mut |_| ControlFlow::Continue(()),
+                    )
+                    .unwrap();
+                    let primes = &components.primes[..components.nprimes];
+                    let private_key = RsaPrivateKey::<$ty, $multi>::from_components(
+                        compute_modulus(primes).unwrap(),
+                        components.e,
+                        components.d,
+                        primes,
+                    )
+                    .unwrap();
+                    assert_eq!(private_key.n().bits(), $size);
+
+                    test_key_basics(&private_key);
+                }
             }
         };
     }
 
-    key_generation!(key_generation_128, 2, 128);
-    key_generation!(key_generation_1024, 2, 1024);
+    key_generation!(key_generation_128, u128, 2, 128);
+    key_generation!(
+        #[ignore = "slow without optimizations"]
+        key_generation_1024,
+        FixedUInt<u32, 32>,
+        2,
+        1024
+    );
+
+    key_generation!(key_generation_multi_3_256, FixedUInt<u32, 8>, 3, 256);
+
+    key_generation!(key_generation_multi_4_64, u64, 4, 64);
+
+    key_generation!(key_generation_multi_5_64, u64, 5, 64);
+
+    key_generation!(
+        #[ignore = "slow without optimizations"]
+        key_generation_multi_8_576,
+        FixedUInt<u32, 18>,
+        8,
+        576
+    );
+
+    key_generation!(
+        #[ignore = "slow without optimizations"]
+        key_generation_multi_16_1024,
+        FixedUInt<u32, 32>,
+        16,
+        1024
+    );
 
-    key_generation!(key_generation_multi_3_256, 3, 256);
+    #[test]
+    fn test_new() {
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        let private_key = RsaPrivateKey::<u64>::new(&mut rng, 64).unwrap();
+        assert_eq!(private_key.n().bits(), 64);
+        assert_eq!(*private_key.e(), 65537);
+        assert_eq!(private_key.primes().len(), 2);
+        assert!(private_key.dp().is_some());
+        test_key_basics(&private_key);
+
+        assert_eq!(
+            RsaPrivateKey::<u64>::new(&mut rng, 128),
+            Err(Error::ModulusTooLarge)
+        );
+    }
 
-    key_generation!(key_generation_multi_4_64, 4, 64);
+    #[test]
+    fn test_new_with_progress() {
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        let mut events = 0;
+        let private_key = RsaPrivateKey::<u64>::new_with_progress(&mut rng, 64, 3, |_| {
+            events += 1;
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert!(events > 0);
+        assert_eq!(*private_key.e(), 3);
+        test_key_basics(&private_key);
+
+        let result =
+            RsaPrivateKey::<u64>::new_with_progress(
+                &mut rng,
+                64,
+                65537,
+                |_| ControlFlow::Break(()),
+            );
+        assert_eq!(result, Err(Error::Cancelled));
+    }
 
-    key_generation!(key_generation_multi_5_64, 5, 64);
-    key_generation!(key_generation_multi_8_576, 8, 576);
-    key_generation!(key_generation_multi_16_1024, 16, 1024);
+    #[test]
+    fn test_validate_rejects_composite_factor() {
+        // 3215031751 is a strong pseudoprime to bases 2, 3, 5 and 7, and
+        // de ≡ 1 mod (p - 1) holds for both factors.
+        let key = RsaPrivateKey::<u64> {
+            e: 65537,
+            d: Some(321_397_364_715_919_973),
+            primes: [4_294_967_291, 3_215_031_751],
+            nprimes: 2,
+            precomputed: None,
+        };
+        assert_eq!(key.validate(), Err(Error::InvalidPrime));
+    }
 
     #[test]
-    #[ignore]
     fn test_negative_decryption_value() {
-        todo!()
+        fn from_le_bytes(bytes: &[u8]) -> u128 {
+            let mut buf = [0u8; 16];
+            buf[..bytes.len()].copy_from_slice(bytes);
+            u128::from_le_bytes(buf)
+        }
+
+        let private_key = RsaPrivateKey::<_>::from_components(
+            from_le_bytes(&[
+                99, 192, 208, 179, 0, 220, 7, 29, 49, 151, 75, 107, 75, 73, 200, 180,
+            ]),
+            from_le_bytes(&[1, 0, 1]),
+            from_le_bytes(&[
+                81, 163, 254, 144, 171, 159, 144, 42, 244, 133, 51, 249, 28, 12, 63, 65,
+            ]),
+            &[
+                from_le_bytes(&[105, 101, 60, 173, 19, 153, 3, 192]),
+                from_le_bytes(&[235, 65, 160, 134, 32, 136, 6, 241]),
+            ],
+        )
+        .unwrap();
+
+        for _ in 0..100 {
+            test_key_basics(&private_key);
+        }
     }
 
     #[test]
@@ -446,26 +1006,277 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
     fn invalid_coeff_private_key_regression() {
-        todo!()
+        // The key of `build_key_from_primes` with qinv + p as its coefficient,
+        // which is congruent to qinv but not reduced.
+        let der = hex!(
+            "3079020100300d06092a864886f70d01010105000465"
+            "3063020100"
+            "021100ce69b1bef3c9b78c12cfb06d72dec059"
+            "0203010001"
+            "021000aba17bf4501a51d6b56cac560977c1"
+            "020900febff20686734721"
+            "020900cf6d05584ef8aa39"
+            "02082b8922137b66b801"
+            "020900af2bd0a1bd3f7ef1"
+            "02090180ecd84931b1cb9b"
+        );
+        assert_eq!(
+            RsaPrivateKey::<u128>::from_pkcs8_der(&der),
+            Err(pkcs8::Error::KeyMalformed)
+        );
+
+        // The same for the coefficient of the third prime of a multi-prime key
+        let der = hex!(
+            "305e020100300d06092a864886f70d0101010500044a"
+            "3048020101"
+            "02087fff3c005edff1f9"
+            "0203010001"
+            "02086cc16282e8d46211"
+            "02031ffff7"
+            "02031fffed"
+            "02031c30bb"
+            "020301d89d"
+            "0203166660"
+            "3011300f02031fffeb020315ecfb0203315535"
+        );
+        assert_eq!(
+            RsaPrivateKey::<u64, 3>::from_pkcs8_der(&der),
+            Err(pkcs8::Error::KeyMalformed)
+        );
     }
 
     #[test]
-    #[ignore]
     fn reject_oversized_private_key() {
-        todo!()
+        // n = 3·(2^4096 + 1) has 4098 bits, more than `RsaPublicKey::MAX_SIZE`.
+        // The size is checked first, so the other components are placeholders.
+        type U4160 = FixedUInt<u32, 130>;
+        let mut n = [0u8; 513];
+        n[0] = 3;
+        n[512] = 3;
+        let mut p = [0u8; 513];
+        p[0] = 1;
+        p[512] = 1;
+
+        let parts: [&[u8]; 5] = [
+            // PrivateKeyInfo, RSAPrivateKey, version and n
+            &hex!("3082043b020100300d06092a864886f70d010101050004820425" "30820421020100" "02820201"),
+            &n,
+            // e, d and p
+            &hex!("0203010001" "020101" "02820201"),
+            &p,
+            // q, dp, dq and qinv
+            &hex!("020103" "020101" "020101" "020101"),
+        ];
+        let mut der = [0u8; 1087];
+        let mut offset = 0;
+        for part in parts {
+            der[offset..offset + part.len()].copy_from_slice(part);
+            offset += part.len();
+        }
+        assert_eq!(offset, der.len());
+
+        assert_eq!(
+            RsaPrivateKey::<U4160>::from_pkcs8_der(&der),
+            Err(pkcs8::Error::KeyMalformed)
+        );
+        assert_eq!(
+            RsaPrivateKey::<U4160>::from_components(
+                be_bytes_to_uint(&n).unwrap(),
+                <U4160 as NumCast>::from(65537).unwrap(),
+                U4160::one(),
+                &[
+                    be_bytes_to_uint(&p).unwrap(),
+                    <U4160 as NumCast>::from(3).unwrap()
+                ],
+            ),
+            Err(Error::ModulusTooLarge)
+        );
     }
 
     #[test]
-    #[ignore]
     fn build_key_from_primes() {
-        todo!()
+        let p = 18_356_656_716_024_858_401_u128;
+        let q = 14_946_608_615_152_724_537;
+        let key = RsaPrivateKey::<_>::from_primes(&[p, q], 65537).unwrap();
+        assert_eq!(key.n(), 274_369_763_417_138_269_028_364_561_594_783_285_337);
+        assert_eq!(
+            key.d(),
+            Some(&164_457_747_780_878_627_659_688_311_963_062_435_073)
+        );
+        assert_eq!(key.dp(), Some(&3_137_076_082_510_313_473));
+        assert_eq!(key.dq(), Some(&12_622_411_773_719_314_161));
+        assert_eq!(key.qinv(), Some(&9_380_125_297_921_131_642));
+        test_key_basics(&key);
+
+        let key = RsaPrivateKey::<u64, 3>::from_primes(&[2_097_143, 2_097_133, 2_097_131], 65537)
+            .unwrap();
+        assert_eq!(key.n(), 9_223_156_534_167_466_489);
+        assert_eq!(key.d(), Some(&7_836_653_140_992_877_073));
+        test_key_basics(&key);
+
+        assert_eq!(
+            RsaPrivateKey::<_>::from_primes(&[p], 65537).unwrap_err(),
+            Error::NprimesTooSmall
+        );
+        assert_eq!(
+            RsaPrivateKey::<_, 3>::from_primes(&[p, q, p], 65537).unwrap_err(),
+            Error::InvalidPrime
+        );
+        assert_eq!(
+            RsaPrivateKey::<u64>::from_primes(&[2_097_143, 2_097_133, 2_097_131], 65537)
+                .unwrap_err(),
+            Error::InvalidArguments
+        );
     }
 
     #[test]
-    #[ignore]
     fn build_key_from_p_q() {
-        todo!()
+        let p = 18_356_656_716_024_858_401_u128;
+        let q = 14_946_608_615_152_724_537;
+        let key = RsaPrivateKey::<_>::from_p_q(p, q, 65537).unwrap();
+        assert_eq!(key.n(), 274_369_763_417_138_269_028_364_561_594_783_285_337);
+        assert_eq!(
+            key.d(),
+            Some(&891_158_051_430_813_451_094_077_652_066_727_873)
+        );
+        assert_eq!(key.dp(), Some(&3_137_076_082_510_313_473));
+        assert_eq!(key.dq(), Some(&12_622_411_773_719_314_161));
+        test_key_basics(&key);
+
+        assert_eq!(
+            RsaPrivateKey::<_>::from_p_q(p, p, 65537).unwrap_err(),
+            Error::InvalidPrime
+        );
+    }
+
+    #[test]
+    fn build_key_recovering_primes() {
+        let key = RsaPrivateKey::<_>::from_components(
+            274_369_763_417_138_269_028_364_561_594_783_285_337_u128,
+            65537,
+            891_158_051_430_813_451_094_077_652_066_727_873,
+            &[],
+        )
+        .unwrap();
+        let mut primes = [key.primes()[0], key.primes()[1]];
+        primes.sort_unstable();
+        assert_eq!(
+            primes,
+            [14_946_608_615_152_724_537, 18_356_656_716_024_858_401]
+        );
+        assert!(key.precomputed.is_some());
+        test_key_basics(&key);
+    }
+
+    #[test]
+    fn build_key_from_crt_components() {
+        let p = 18_356_656_716_024_858_401_u128;
+        let q = 14_946_608_615_152_724_537;
+        let (dp, dq, qinv) = (
+            3_137_076_082_510_313_473,
+            12_622_411_773_719_314_161,
+            9_380_125_297_921_131_642,
+        );
+        let mut key = RsaPrivateKey::<_>::from_crt_components(65537, p, q, dp, dq, qinv).unwrap();
+        assert_eq!(key.n(), 274_369_763_417_138_269_028_364_561_594_783_285_337);
+        assert_eq!(key.d(), None);
+        assert_eq!(
+            key.private_exponent(),
+            Ok(891_158_051_430_813_451_094_077_652_066_727_873)
+        );
+        test_key_basics(&key);
+
+        // Implicit rejection hashes the CRT values, as d is not there.
+        let full_key = RsaPrivateKey::<_>::from_p_q(p, q, 65537).unwrap();
+        assert_ne!(
+            key.implicit_rejection_secret(),
+            full_key.implicit_rejection_secret()
+        );
+        assert_eq!(
+            key.implicit_rejection_secret(),
+            RsaPrivateKey::<_>::from_crt_components(65537, p, q, dp, dq, qinv)
+                .unwrap()
+                .implicit_rejection_secret()
+        );
+
+        // Without d, the CRT values are all there is.
+        key.clear_precomputed();
+        assert_eq!(key.qinv(), Some(&qinv));
+        assert_eq!(key.precompute(), Ok(()));
+        test_key_basics(&key);
+
+        assert_eq!(
+            RsaPrivateKey::<_>::from_crt_components(65537, p, q, dp + 2, dq, qinv).unwrap_err(),
+            Error::InvalidExponent
+        );
+        assert_eq!(
+            RsaPrivateKey::<_>::from_crt_components(65537, p, q, dp + p - 1, dq, qinv).unwrap_err(),
+            Error::InvalidExponent
+        );
+        assert_eq!(
+            RsaPrivateKey::<_>::from_crt_components(65537, p, q, dp, dq, qinv + 1).unwrap_err(),
+            Error::InvalidCoefficient
+        );
+        assert_eq!(
+            RsaPrivateKey::<_>::from_crt_components(65537, p, p, dp, dp, qinv).unwrap_err(),
+            Error::InvalidPrime
+        );
+    }
+
+    #[test]
+    fn build_key_from_multi_prime_crt_components() {
+        let primes = [2_097_143_u64, 2_097_133, 2_097_131];
+        let full_key = RsaPrivateKey::<_, 3>::from_primes(&primes, 65537).unwrap();
+        let (dp, dq, qinv) = (
+            *full_key.dp().unwrap(),
+            *full_key.dq().unwrap(),
+            *full_key.qinv().unwrap(),
+        );
+        let crt_value = &full_key.crt_values().unwrap()[0];
+        let (exp, coeff) = (crt_value.exp, crt_value.coeff);
+
+        let key = RsaPrivateKey::<_, 3>::from_multi_prime_crt_components(
+            65537,
+            &primes,
+            &[dp, dq, exp],
+            &[qinv, coeff],
+        )
+        .unwrap();
+        assert_eq!(key.n(), 9_223_156_534_167_466_489);
+        assert_eq!(key.d(), None);
+        assert_eq!(key.private_exponent(), Ok(7_836_653_140_992_877_073));
+        test_key_basics(&key);
+
+        assert_eq!(
+            RsaPrivateKey::<_, 3>::from_multi_prime_crt_components(
+                65537,
+                &primes,
+                &[dp, dq, exp],
+                &[qinv, coeff + 1],
+            )
+            .unwrap_err(),
+            Error::InvalidCoefficient
+        );
+        assert_eq!(
+            RsaPrivateKey::<_, 3>::from_multi_prime_crt_components(
+                65537,
+                &primes,
+                &[dp, dq],
+                &[qinv, coeff],
+            )
+            .unwrap_err(),
+            Error::InvalidArguments
+        );
+        assert_eq!(
+            RsaPrivateKey::<_>::from_multi_prime_crt_components(
+                65537,
+                &primes,
+                &[dp, dq, exp],
+                &[qinv, coeff],
+            )
+            .unwrap_err(),
+            Error::InvalidArguments
+        );
     }
 }
@@ -24,10 +24,13 @@ pub mod encoding;
 pub mod errors;
 pub mod oaep;
 pub mod pkcs1v15;
+pub mod primality;
 pub mod pss;
+pub mod signature_algorithm;
 pub mod traits;
 
 mod dummy_rng;
+mod dyn_digest;
 mod key;
 
 mod prefix;
@@ -36,6 +39,8 @@ mod prefix;
 pub use sha2;
 
 pub use crate::{
+    algorithms::generate::KeyGenProgress,
+    dyn_digest::HashAlgorithm,
     errors::{Error, Result},
     key::{RsaPrivateKey, RsaPublicKey},
     oaep::Oaep,
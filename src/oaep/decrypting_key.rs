@@ -0,0 +1,136 @@
+use super::{decrypt_digest, to_label, EncryptingKey};
+use crate::dummy_rng::DummyRng;
+use crate::traits::{Decryptor, EncryptingKeypair, RandomizedDecryptor, UnsignedModularInt};
+use crate::{Result, RsaPrivateKey};
+use core::marker::PhantomData;
+use digest::{Digest, FixedOutputReset};
+use rand_core::CryptoRngCore;
+use zeroize::ZeroizeOnDrop;
+
+use heapless::String;
+
+/// Decryption key for OAEP decryption as described in [RFC8017 § 7.1].
+///
+/// [RFC8017 § 7.1]: https://datatracker.ietf.org/doc/html/rfc8017#section-7.1
+#[derive(Debug, Clone)]
+pub struct DecryptingKey<T, D, MGD = D, const MAX_PRIMES: usize = 2>
+where
+    D: Digest,
+    MGD: Digest + FixedOutputReset,
+    T: UnsignedModularInt,
+{
+    inner: RsaPrivateKey<T, MAX_PRIMES>,
+    label: Option<String<128>>,
+    phantom: PhantomData<D>,
+    mg_phantom: PhantomData<MGD>,
+}
+
+impl<T, D, MGD, const MAX_PRIMES: usize> DecryptingKey<T, D, MGD, MAX_PRIMES>
+where
+    D: Digest,
+    MGD: Digest + FixedOutputReset,
+    T: UnsignedModularInt,
+{
+    /// Create a new decrypting key from an RSA private key.
+    pub fn new(key: RsaPrivateKey<T, MAX_PRIMES>) -> Self {
+        Self {
+            inner: key,
+            label: None,
+            phantom: Default::default(),
+            mg_phantom: Default::default(),
+        }
+    }
+
+    /// Create a new decrypting key from an RSA private key using provided label
+    ///
+    /// # Panics
+    ///
+    /// Panics if `label` is longer than 128 bytes.
+    pub fn new_with_label<S: AsRef<str>>(key: RsaPrivateKey<T, MAX_PRIMES>, label: S) -> Self {
+        Self {
+            inner: key,
+            label: Some(to_label(label)),
+            phantom: Default::default(),
+            mg_phantom: Default::default(),
+        }
+    }
+}
+
+impl<T, D, MGD, const MAX_PRIMES: usize> Decryptor for DecryptingKey<T, D, MGD, MAX_PRIMES>
+where
+    D: Digest,
+    MGD: Digest + FixedOutputReset,
+    T: UnsignedModularInt,
+{
+    fn decrypt<'a>(&self, ciphertext: &[u8], storage: &'a mut [u8]) -> Result<&'a [u8]> {
+        decrypt_digest::<_, DummyRng, D, MGD, MAX_PRIMES>(
+            None,
+            &self.inner,
+            ciphertext,
+            self.label.as_ref().cloned(),
+            storage,
+        )
+    }
+}
+
+impl<T, D, MGD, const MAX_PRIMES: usize> RandomizedDecryptor
+    for DecryptingKey<T, D, MGD, MAX_PRIMES>
+where
+    D: Digest,
+    MGD: Digest + FixedOutputReset,
+    T: UnsignedModularInt,
+{
+    fn decrypt_with_rng<'a, R: CryptoRngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+        ciphertext: &[u8],
+        storage: &'a mut [u8],
+    ) -> Result<&'a [u8]> {
+        decrypt_digest::<_, _, D, MGD, MAX_PRIMES>(
+            Some(rng),
+            &self.inner,
+            ciphertext,
+            self.label.as_ref().cloned(),
+            storage,
+        )
+    }
+}
+
+impl<T, D, MGD, const MAX_PRIMES: usize> EncryptingKeypair for DecryptingKey<T, D, MGD, MAX_PRIMES>
+where
+    D: Digest + Clone,
+    MGD: Digest + FixedOutputReset + Clone,
+    T: UnsignedModularInt,
+{
+    type EncryptingKey = EncryptingKey<T, D, MGD>;
+    fn encrypting_key(&self) -> EncryptingKey<T, D, MGD> {
+        EncryptingKey {
+            inner: self.inner.to_public_key(),
+            label: self.label.clone(),
+            phantom: Default::default(),
+            mg_phantom: Default::default(),
+        }
+    }
+}
+
+impl<T, D, MGD, const MAX_PRIMES: usize> ZeroizeOnDrop for DecryptingKey<T, D, MGD, MAX_PRIMES>
+where
+    D: Digest,
+    MGD: Digest + FixedOutputReset,
+    T: UnsignedModularInt,
+{
+}
+
+impl<T, D, MGD, const MAX_PRIMES: usize> PartialEq for DecryptingKey<T, D, MGD, MAX_PRIMES>
+where
+    D: Digest,
+    MGD: Digest + FixedOutputReset,
+    T: UnsignedModularInt,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner && self.label == other.label
+    }
+}
+
+#[cfg(test)]
+mod tests {}
@@ -1,4 +1,4 @@
-use super::encrypt_digest;
+use super::{encrypt_digest, to_label};
 use crate::{
     traits::{RandomizedEncryptor, UnsignedModularInt},
     Result, RsaPublicKey,
@@ -19,10 +19,10 @@ where
     MGD: Digest + FixedOutputReset,
     T: UnsignedModularInt,
 {
-    inner: RsaPublicKey<T>,
-    label: Option<String<128>>, // todo: make it not fixed
-    phantom: PhantomData<D>,
-    mg_phantom: PhantomData<MGD>,
+    pub(super) inner: RsaPublicKey<T>,
+    pub(super) label: Option<String<128>>, // todo: make it not fixed
+    pub(super) phantom: PhantomData<D>,
+    pub(super) mg_phantom: PhantomData<MGD>,
 }
 
 impl<T, D, MGD> EncryptingKey<T, D, MGD>
@@ -42,10 +42,14 @@ where
     }
 
     /// Create a new verifying key from an RSA public key using provided label
+    ///
+    /// # Panics
+    ///
+    /// Panics if `label` is longer than 128 bytes.
     pub fn new_with_label<S: AsRef<str>>(key: RsaPublicKey<T>, label: S) -> Self {
         Self {
             inner: key,
-            label: None,
+            label: Some(to_label(label)),
             phantom: Default::default(),
             mg_phantom: Default::default(),
         }
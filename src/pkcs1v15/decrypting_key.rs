@@ -1,48 +1,113 @@
-use super::{decrypt, EncryptingKey};
+use super::{decrypt, decrypt_tls_pre_master_secret, EncryptingKey};
+use crate::algorithms::pkcs1v15::TLS_PRE_MASTER_SECRET_LEN;
+use crate::dummy_rng::DummyRng;
 use crate::traits::UnsignedModularInt;
 use crate::{
     key::RsaPrivateKey,
     traits::{Decryptor, EncryptingKeypair, RandomizedDecryptor},
     Result,
 };
+use rand_core::CryptoRngCore;
 use zeroize::ZeroizeOnDrop;
 
 /// Decryption key for PKCS#1 v1.5 decryption as described in [RFC8017 § 7.2].
 ///
 /// [RFC8017 § 7.2]: https://datatracker.ietf.org/doc/html/rfc8017#section-7.2
 #[derive(Debug, Clone, PartialEq)]
-pub struct DecryptingKey<T>
+pub struct DecryptingKey<T, const MAX_PRIMES: usize = 2>
 where
     T: UnsignedModularInt,
 {
-    inner: RsaPrivateKey<T>,
+    inner: RsaPrivateKey<T, MAX_PRIMES>,
 }
 
-impl<T> DecryptingKey<T>
+impl<T, const MAX_PRIMES: usize> DecryptingKey<T, MAX_PRIMES>
 where
     T: UnsignedModularInt,
 {
     /// Create a new verifying key from an RSA public key.
-    pub fn new(key: RsaPrivateKey<T>) -> Self {
+    pub fn new(key: RsaPrivateKey<T, MAX_PRIMES>) -> Self {
         Self { inner: key }
     }
+
+    /// Decrypt the pre-master secret sent by a client in the TLS 1.2 RSA key
+    /// exchange, following [RFC 5246 § 7.4.7.1].
+    ///
+    /// `client_version` is the version from the ClientHello and `random`
+    /// must be 48 freshly generated random bytes. A ciphertext with invalid
+    /// padding, or one that does not hold 48 bytes, decrypts to `random`
+    /// instead of failing, and the caller can't tell the difference. The
+    /// handshake then fails at the Finished message instead.
+    ///
+    /// [RFC 5246 § 7.4.7.1]: https://datatracker.ietf.org/doc/html/rfc5246#section-7.4.7.1
+    pub fn decrypt_tls_pre_master_secret(
+        &self,
+        ciphertext: &[u8],
+        client_version: [u8; 2],
+        random: &[u8; TLS_PRE_MASTER_SECRET_LEN],
+    ) -> Result<[u8; TLS_PRE_MASTER_SECRET_LEN]> {
+        decrypt_tls_pre_master_secret::<_, DummyRng, MAX_PRIMES>(
+            None,
+            &self.inner,
+            ciphertext,
+            client_version,
+            random,
+        )
+    }
+
+    /// Decrypt the pre-master secret like
+    /// [`Self::decrypt_tls_pre_master_secret`], using RSA blinding with the
+    /// given random source.
+    pub fn decrypt_tls_pre_master_secret_with_rng<R: CryptoRngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+        ciphertext: &[u8],
+        client_version: [u8; 2],
+        random: &[u8; TLS_PRE_MASTER_SECRET_LEN],
+    ) -> Result<[u8; TLS_PRE_MASTER_SECRET_LEN]> {
+        decrypt_tls_pre_master_secret(Some(rng), &self.inner, ciphertext, client_version, random)
+    }
 }
 
-impl<T> Decryptor for DecryptingKey<T> where T: UnsignedModularInt {}
+impl<T, const MAX_PRIMES: usize> Decryptor for DecryptingKey<T, MAX_PRIMES>
+where
+    T: UnsignedModularInt,
+{
+    fn decrypt<'a>(&self, ciphertext: &[u8], storage: &'a mut [u8]) -> Result<&'a [u8]> {
+        decrypt::<_, DummyRng, MAX_PRIMES>(None, &self.inner, ciphertext, storage)
+    }
+}
 
-impl<T> RandomizedDecryptor for DecryptingKey<T> where T: UnsignedModularInt {}
+impl<T, const MAX_PRIMES: usize> RandomizedDecryptor for DecryptingKey<T, MAX_PRIMES>
+where
+    T: UnsignedModularInt,
+{
+    fn decrypt_with_rng<'a, R: CryptoRngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+        ciphertext: &[u8],
+        storage: &'a mut [u8],
+    ) -> Result<&'a [u8]> {
+        decrypt(Some(rng), &self.inner, ciphertext, storage)
+    }
+}
 
-impl<T> EncryptingKeypair for DecryptingKey<T>
+impl<T, const MAX_PRIMES: usize> EncryptingKeypair for DecryptingKey<T, MAX_PRIMES>
 where
     T: UnsignedModularInt,
 {
     type EncryptingKey = EncryptingKey<T>;
     fn encrypting_key(&self) -> EncryptingKey<T> {
-        todo!()
+        EncryptingKey {
+            inner: self.inner.to_public_key(),
+        }
     }
 }
 
-impl<T> ZeroizeOnDrop for DecryptingKey<T> where T: UnsignedModularInt {}
+impl<T, const MAX_PRIMES: usize> ZeroizeOnDrop for DecryptingKey<T, MAX_PRIMES> where
+    T: UnsignedModularInt
+{
+}
 
 #[cfg(test)]
 mod tests {}
@@ -1,5 +1,6 @@
 //! `RSASSA-PKCS1-v1_5` signatures.
 
+use core::borrow::Borrow;
 use core::fmt::{Debug, Display, Formatter, LowerHex, UpperHex};
 
 use crate::{algorithms::pad::uint_to_be_pad, traits::UnsignedModularInt};
@@ -31,12 +32,36 @@ where
     }
 }
 
+impl<T> Signature<T>
+where
+    T: UnsignedModularInt,
+{
+    /// Writes the `len` big-endian bytes of the signature as hex.
+    fn fmt_hex(&self, f: &mut Formatter<'_>, upper: bool) -> core::fmt::Result {
+        let bytes = self.inner.to_be_bytes();
+        let bytes: &[u8] = bytes.borrow();
+        for _ in bytes.len()..self.len {
+            write!(f, "00")?;
+        }
+        for byte in &bytes[bytes.len().saturating_sub(self.len)..] {
+            if upper {
+                write!(f, "{:02X}", byte)?;
+            } else {
+                write!(f, "{:02x}", byte)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<T> Debug for Signature<T>
 where
     T: UnsignedModularInt,
 {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> core::result::Result<(), core::fmt::Error> {
-        todo!()
+        fmt.debug_tuple("Signature")
+            .field(&format_args!("{:X}", self))
+            .finish()
     }
 }
 
@@ -45,7 +70,7 @@ where
     T: UnsignedModularInt,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        todo!()
+        self.fmt_hex(f, false)
     }
 }
 
@@ -54,7 +79,7 @@ where
     T: UnsignedModularInt,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        todo!()
+        self.fmt_hex(f, true)
     }
 }
 
@@ -68,4 +93,18 @@ where
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_formatting() {
+        let sig = Signature {
+            inner: 0xDEAD_BEEF_u64,
+            len: 5,
+        };
+        assert_eq!(format!("{:x}", sig), "00deadbeef");
+        assert_eq!(format!("{:X}", sig), "00DEADBEEF");
+        assert_eq!(format!("{}", sig), "00DEADBEEF");
+        assert_eq!(format!("{:?}", sig), "Signature(00DEADBEEF)");
+    }
+}
@@ -10,28 +10,28 @@ use signature::{
 };
 use zeroize::ZeroizeOnDrop;
 
-use crate::traits::UnsignedModularInt;
+use crate::traits::{PublicKeyParts, UnsignedModularInt};
 
 /// Signing key for `RSASSA-PKCS1-v1_5` signatures as described in [RFC8017 § 8.2].
 ///
 /// [RFC8017 § 8.2]: https://datatracker.ietf.org/doc/html/rfc8017#section-8.2
 #[derive(Debug, Clone)]
-pub struct SigningKey<D, T>
+pub struct SigningKey<D, T, const MAX_PRIMES: usize = 2>
 where
     T: UnsignedModularInt,
 {
-    inner: RsaPrivateKey<T>,
+    inner: RsaPrivateKey<T, MAX_PRIMES>,
     prefix: Prefix,
     phantom: PhantomData<D>,
 }
 
-impl<D, T> SigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> SigningKey<D, T, MAX_PRIMES>
 where
     D: Digest + AssociatedOid,
     T: UnsignedModularInt,
 {
     /// Create a new signing key with a prefix for the digest `D`.
-    pub fn new(key: RsaPrivateKey<T>) -> Self {
+    pub fn new(key: RsaPrivateKey<T, MAX_PRIMES>) -> Self {
         Self {
             inner: key,
             prefix: pkcs1v15_generate_prefix::<D>(),
@@ -40,12 +40,16 @@ where
     }
 
     /// Generate a new signing key with a prefix for the digest `D`.
-    pub fn random(bit_size: usize) -> Result<Self> {
-        todo!()
+    pub fn random<R: CryptoRngCore + ?Sized>(rng: &mut R, bit_size: usize) -> Result<Self> {
+        Ok(Self {
+            inner: RsaPrivateKey::new(rng, bit_size)?,
+            prefix: pkcs1v15_generate_prefix::<D>(),
+            phantom: Default::default(),
+        })
     }
 }
 
-impl<D, T> SigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> SigningKey<D, T, MAX_PRIMES>
 where
     T: UnsignedModularInt,
 {
@@ -54,7 +58,7 @@ where
     /// ## Note: unprefixed signatures are uncommon
     ///
     /// In most cases you'll want to use [`SigningKey::new`].
-    pub fn new_unprefixed(key: RsaPrivateKey<T>) -> Self {
+    pub fn new_unprefixed(key: RsaPrivateKey<T, MAX_PRIMES>) -> Self {
         Self {
             inner: key,
             prefix: Default::default(),
@@ -63,8 +67,99 @@ where
     }
 
     /// Generate a new signing key with an empty prefix.
-    pub fn random_unprefixed(bit_size: usize) -> Result<Self> {
-        todo!()
+    pub fn random_unprefixed<R: CryptoRngCore + ?Sized>(
+        rng: &mut R,
+        bit_size: usize,
+    ) -> Result<Self> {
+        Ok(Self {
+            inner: RsaPrivateKey::new(rng, bit_size)?,
+            prefix: Default::default(),
+            phantom: Default::default(),
+        })
+    }
+}
+
+impl<D, T, const MAX_PRIMES: usize> SigningKey<D, T, MAX_PRIMES>
+where
+    T: UnsignedModularInt,
+{
+    /// Signs an already hashed message, blinding if `rng` is given.
+    fn sign_hashed<R: CryptoRngCore + ?Sized>(
+        &self,
+        rng: Option<&mut R>,
+        hashed: &[u8],
+    ) -> Result<Signature<T>> {
+        // The bytes of `n` are as long as the modulus, enough to hold the encoded message.
+        let mut storage = self.inner.n().to_be_bytes();
+        sign(rng, &self.inner, &self.prefix, hashed, storage.as_mut())
+    }
+}
+
+//
+// `*Signer` trait impls
+//
+
+impl<D, T, const MAX_PRIMES: usize> DigestSigner<D, Signature<T>> for SigningKey<D, T, MAX_PRIMES>
+where
+    D: Digest,
+    T: UnsignedModularInt,
+{
+    fn try_sign_digest(&self, digest: D) -> signature::Result<Signature<T>> {
+        self.sign_hashed::<DummyRng>(None, &digest.finalize())
+            .map_err(|e| e.into())
+    }
+}
+
+impl<D, T, const MAX_PRIMES: usize> PrehashSigner<Signature<T>> for SigningKey<D, T, MAX_PRIMES>
+where
+    D: Digest,
+    T: UnsignedModularInt,
+{
+    fn sign_prehash(&self, prehash: &[u8]) -> signature::Result<Signature<T>> {
+        self.sign_hashed::<DummyRng>(None, prehash)
+            .map_err(|e| e.into())
+    }
+}
+
+impl<D, T, const MAX_PRIMES: usize> RandomizedDigestSigner<D, Signature<T>>
+    for SigningKey<D, T, MAX_PRIMES>
+where
+    D: Digest,
+    T: UnsignedModularInt,
+{
+    fn try_sign_digest_with_rng(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        digest: D,
+    ) -> signature::Result<Signature<T>> {
+        self.sign_hashed(Some(rng), &digest.finalize())
+            .map_err(|e| e.into())
+    }
+}
+
+impl<D, T, const MAX_PRIMES: usize> RandomizedSigner<Signature<T>> for SigningKey<D, T, MAX_PRIMES>
+where
+    D: Digest,
+    T: UnsignedModularInt,
+{
+    fn try_sign_with_rng(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        msg: &[u8],
+    ) -> signature::Result<Signature<T>> {
+        self.sign_hashed(Some(rng), &D::digest(msg))
+            .map_err(|e| e.into())
+    }
+}
+
+impl<D, T, const MAX_PRIMES: usize> Signer<Signature<T>> for SigningKey<D, T, MAX_PRIMES>
+where
+    D: Digest,
+    T: UnsignedModularInt,
+{
+    fn try_sign(&self, msg: &[u8]) -> signature::Result<Signature<T>> {
+        self.sign_hashed::<DummyRng>(None, &D::digest(msg))
+            .map_err(|e| e.into())
     }
 }
 
@@ -72,34 +167,37 @@ where
 // Other trait impls
 //
 
-impl<D, T> AsRef<RsaPrivateKey<T>> for SigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> AsRef<RsaPrivateKey<T, MAX_PRIMES>>
+    for SigningKey<D, T, MAX_PRIMES>
 where
     T: UnsignedModularInt,
 {
-    fn as_ref(&self) -> &RsaPrivateKey<T> {
+    fn as_ref(&self) -> &RsaPrivateKey<T, MAX_PRIMES> {
         &self.inner
     }
 }
 
-impl<D, T> From<RsaPrivateKey<T>> for SigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> From<RsaPrivateKey<T, MAX_PRIMES>>
+    for SigningKey<D, T, MAX_PRIMES>
 where
     T: UnsignedModularInt,
 {
-    fn from(key: RsaPrivateKey<T>) -> Self {
+    fn from(key: RsaPrivateKey<T, MAX_PRIMES>) -> Self {
         Self::new_unprefixed(key)
     }
 }
 
-impl<D, T> From<SigningKey<D, T>> for RsaPrivateKey<T>
+impl<D, T, const MAX_PRIMES: usize> From<SigningKey<D, T, MAX_PRIMES>>
+    for RsaPrivateKey<T, MAX_PRIMES>
 where
     T: UnsignedModularInt,
 {
-    fn from(key: SigningKey<D, T>) -> Self {
+    fn from(key: SigningKey<D, T, MAX_PRIMES>) -> Self {
         key.inner
     }
 }
 
-impl<D, T> Keypair for SigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> Keypair for SigningKey<D, T, MAX_PRIMES>
 where
     D: Digest,
     T: UnsignedModularInt,
@@ -110,14 +208,18 @@ where
         VerifyingKey {
             inner: self.inner.to_public_key(),
             prefix: self.prefix.clone(),
+            accept_missing_null: false,
             phantom: Default::default(),
         }
     }
 }
 
-impl<D, T> ZeroizeOnDrop for SigningKey<D, T> where T: UnsignedModularInt {}
+impl<D, T, const MAX_PRIMES: usize> ZeroizeOnDrop for SigningKey<D, T, MAX_PRIMES> where
+    T: UnsignedModularInt
+{
+}
 
-impl<D, T> PartialEq for SigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> PartialEq for SigningKey<D, T, MAX_PRIMES>
 where
     T: UnsignedModularInt,
 {
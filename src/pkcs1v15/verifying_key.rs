@@ -18,6 +18,7 @@ where
 {
     pub(super) inner: RsaPublicKey<T>,
     pub(super) prefix: Prefix,
+    pub(super) accept_missing_null: bool,
     pub(super) phantom: PhantomData<D>,
 }
 
@@ -31,9 +32,22 @@ where
         Self {
             inner: key,
             prefix: pkcs1v15_generate_prefix::<D>(),
+            accept_missing_null: false,
             phantom: Default::default(),
         }
     }
+
+    /// Create a new verifying key with a prefix for the digest `D`, which
+    /// also accepts signatures whose `DigestInfo` lacks the NULL parameters of
+    /// the digest `AlgorithmIdentifier`.
+    ///
+    /// Some HSMs emit such signatures.
+    pub fn new_accepting_missing_null(key: RsaPublicKey<T>) -> Self {
+        Self {
+            accept_missing_null: true,
+            ..Self::new(key)
+        }
+    }
 }
 
 impl<D, T> VerifyingKey<D, T>
@@ -49,6 +63,7 @@ where
         Self {
             inner: key,
             prefix: Default::default(),
+            accept_missing_null: false,
             phantom: Default::default(),
         }
     }
@@ -64,13 +79,14 @@ where
     T: UnsignedModularInt,
 {
     fn verify_digest(&self, digest: D, signature: &Signature<T>) -> signature::Result<()> {
-        let mut cloned_t = (*self.inner.n()).to_be_bytes();
+        let mut cloned_t = self.inner.n().to_be_bytes();
         verify(
             &self.inner,
             &self.prefix,
             &digest.finalize(),
             &signature.inner,
             signature.len,
+            self.accept_missing_null,
             cloned_t.as_mut(),
         )
         .map_err(|e| e.into())
@@ -83,13 +99,14 @@ where
     T: UnsignedModularInt,
 {
     fn verify_prehash(&self, prehash: &[u8], signature: &Signature<T>) -> signature::Result<()> {
-        let mut cloned_t = (*self.inner.n()).to_be_bytes();
+        let mut cloned_t = self.inner.n().to_be_bytes();
         verify(
             &self.inner,
             &self.prefix,
             prehash,
             &signature.inner,
             signature.len,
+            self.accept_missing_null,
             cloned_t.as_mut(),
         )
         .map_err(|e| e.into())
@@ -102,13 +119,14 @@ where
     T: UnsignedModularInt + core::fmt::Debug,
 {
     fn verify(&self, msg: &[u8], signature: &Signature<T>) -> Result<(), signature::Error> {
-        let mut cloned_t = (*self.inner.n()).to_be_bytes();
+        let mut cloned_t = self.inner.n().to_be_bytes();
         verify(
             &self.inner,
             &self.prefix.clone(),
             &D::digest(msg),
             &signature.inner,
             signature.len,
+            self.accept_missing_null,
             cloned_t.as_mut(),
         )
         .map_err(|e| e.into())
@@ -137,6 +155,7 @@ where
         Self {
             inner: self.inner.clone(),
             prefix: self.prefix.clone(),
+            accept_missing_null: self.accept_missing_null,
             phantom: Default::default(),
         }
     }
@@ -165,18 +184,11 @@ where
     T: UnsignedModularInt,
 {
     fn eq(&self, other: &Self) -> bool {
-        self.inner == other.inner && self.prefix == other.prefix
+        self.inner == other.inner
+            && self.prefix == other.prefix
+            && self.accept_missing_null == other.accept_missing_null
     }
 }
 
 #[cfg(test)]
-mod tests {
-    #[test]
-    #[ignore]
-    #[cfg(feature = "serde")]
-    fn test_serde() {
-        use super::*;
-        use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
-        todo!()
-    }
-}
+mod tests {}
@@ -0,0 +1,481 @@
+//! Primality tests for [`UnsignedModularInt`] types.
+//!
+//! All tests run on the stack and work for any `T` implementing
+//! [`UnsignedModularInt`], e.g. primitive integers, `FixedUInt` or
+//! `crypto-bigint` types. Exponentiations use the crate's Montgomery modular
+//! exponentiation, and are not constant time.
+//!
+//! [`baillie_psw`] is the recommended test: no composite passing it is known.
+//! [`miller_rabin`] and [`strong_lucas`] are its building blocks, and can be
+//! used on their own, e.g. to run additional Miller-Rabin rounds with chosen
+//! witnesses. [`miller_rabin_deterministic`] proves candidates of up to
+//! [`MILLER_RABIN_DETERMINISTIC_BITS`] bits prime or composite.
+
+use num_traits::{NumCast, ToPrimitive};
+use zeroize::Zeroize;
+
+use crate::algorithms::modular::ct::{ct_add_mod, ct_sub_mod};
+use crate::algorithms::modular::exp::mod_exp;
+use crate::algorithms::modular::inverse::half_mod;
+use crate::algorithms::modular::montgomery::{type_bits, Montgomery};
+use crate::traits::UnsignedModularInt;
+
+/// Odd primes below 1000, used for trial division and sieving.
+pub(crate) const SMALL_PRIMES: [u16; 167] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269, 271, 277, 281, 283, 293, 307,
+    311, 313, 317, 331, 337, 347, 349, 353, 359, 367, 373, 379, 383, 389, 397, 401, 409, 419, 421,
+    431, 433, 439, 443, 449, 457, 461, 463, 467, 479, 487, 491, 499, 503, 509, 521, 523, 541, 547,
+    557, 563, 569, 571, 577, 587, 593, 599, 601, 607, 613, 617, 619, 631, 641, 643, 647, 653, 659,
+    661, 673, 677, 683, 691, 701, 709, 719, 727, 733, 739, 743, 751, 757, 761, 769, 773, 787, 797,
+    809, 811, 821, 823, 827, 829, 839, 853, 857, 859, 863, 877, 881, 883, 887, 907, 911, 919, 929,
+    937, 941, 947, 953, 967, 971, 977, 983, 991, 997,
+];
+
+/// Computes `value mod p` for each of the [`SMALL_PRIMES`].
+pub(crate) fn small_prime_residues<T>(value: &T, residues: &mut [u16; SMALL_PRIMES.len()])
+where
+    T: UnsignedModularInt,
+{
+    let mut bytes = value.to_be_bytes();
+    for (residue, p) in residues.iter_mut().zip(SMALL_PRIMES.iter()) {
+        let p = *p as u32;
+        let mut r = 0u32;
+        for byte in bytes.as_ref() {
+            r = ((r << 8) | *byte as u32) % p;
+        }
+        *residue = r as u16;
+    }
+    bytes.as_mut().zeroize();
+}
+
+/// Baillie-PSW primality test.
+///
+/// Rejects multiples of small primes by trial division, then runs a
+/// Miller-Rabin test with witness 2 followed by a strong Lucas test.
+pub fn baillie_psw<T>(candidate: &T) -> bool
+where
+    T: UnsignedModularInt,
+{
+    let two = T::one() + T::one();
+    if *candidate < two {
+        return false;
+    }
+    if candidate.is_even() {
+        return *candidate == two;
+    }
+
+    let mut residues = [0u16; SMALL_PRIMES.len()];
+    small_prime_residues(candidate, &mut residues);
+    for (residue, p) in residues.iter().zip(SMALL_PRIMES.iter()) {
+        if *residue == 0 {
+            return candidate.to_u16() == Some(*p);
+        }
+    }
+    if candidate.to_u32().is_some_and(|c| c < 1_000_000) {
+        // No factor below 1000, so it is prime.
+        return true;
+    }
+
+    miller_rabin(candidate, &two) && strong_lucas(candidate)
+}
+
+/// Miller-Rabin test of `candidate` with the given `witness`.
+///
+/// Returns `true` if `candidate` is a strong probable prime to base `witness`,
+/// and `false` if `witness` proves it composite. Witnesses that are multiples
+/// of `candidate` do not prove anything, and `true` is returned for them.
+pub fn miller_rabin<T>(candidate: &T, witness: &T) -> bool
+where
+    T: UnsignedModularInt,
+{
+    let two = T::one() + T::one();
+    if *candidate <= two {
+        return *candidate == two;
+    }
+    if candidate.is_even() {
+        return false;
+    }
+    let witness = *witness % *candidate;
+    if witness.is_zero() {
+        return true;
+    }
+
+    let minus_one = *candidate - T::one();
+    let s = minus_one.trailing_zeros() as usize;
+    let d = minus_one >> s;
+
+    let x = mod_exp(witness, d, *candidate);
+    if x.is_one() || x == minus_one {
+        return true;
+    }
+
+    let mont = match Montgomery::new(*candidate) {
+        Some(mont) => mont,
+        None => return false,
+    };
+    let minus_one = mont.montgomery_form(minus_one);
+    let mut x = mont.montgomery_form(x);
+    for _ in 1..s {
+        x = mont.square(x);
+        if x == minus_one {
+            return true;
+        }
+        if x == mont.one() {
+            return false;
+        }
+    }
+    false
+}
+
+/// Candidates of up to this many bits are below 3.3 * 10^24, the smallest
+/// composite that is a strong probable prime to all of the first 13 primes
+/// (Sorenson and Webster, 2015).
+pub const MILLER_RABIN_DETERMINISTIC_BITS: usize = 81;
+
+/// The first 13 primes.
+const DETERMINISTIC_WITNESSES: [u8; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Miller-Rabin test of `candidate` with the first 13 primes as witnesses,
+/// which is deterministic for candidates of up to
+/// [`MILLER_RABIN_DETERMINISTIC_BITS`] bits.
+///
+/// Returns `None` for larger candidates, for which no fixed set of witnesses
+/// is known to be sufficient. Use [`baillie_psw`] for them.
+pub fn miller_rabin_deterministic<T>(candidate: &T) -> Option<bool>
+where
+    T: UnsignedModularInt,
+{
+    if candidate.bits() > MILLER_RABIN_DETERMINISTIC_BITS {
+        return None;
+    }
+
+    for witness in DETERMINISTIC_WITNESSES {
+        if !miller_rabin(candidate, &<T as NumCast>::from(witness)?) {
+            return Some(false);
+        }
+    }
+    Some(true)
+}
+
+/// Strong Lucas probable prime test, with parameters chosen by Selfridge's
+/// method A: `D` is the first of 5, -7, 9, -11, ... with Jacobi symbol
+/// `(D/n) = -1`, `P = 1` and `Q = (1 - D) / 4`.
+pub fn strong_lucas<T>(candidate: &T) -> bool
+where
+    T: UnsignedModularInt,
+{
+    let n = *candidate;
+    let two = T::one() + T::one();
+    if n <= two {
+        return n == two;
+    }
+    if n.is_even() {
+        return false;
+    }
+    // No suitable `D` exists for perfect squares.
+    if is_square(&n) {
+        return false;
+    }
+
+    let mut d: i64 = 5;
+    loop {
+        match jacobi(d, &n) {
+            -1 => break,
+            // `n` shares a factor with `D`
+            0 if <T as NumCast>::from(d.unsigned_abs()) != Some(n) => return false,
+            _ => {}
+        }
+        d = if d > 0 { -(d + 2) } else { -d + 2 };
+    }
+    let q = (1 - d) / 4;
+
+    let mont = match Montgomery::new(n) {
+        Some(mont) => mont,
+        None => return false,
+    };
+    let d_mont = mont.montgomery_form(signed_mod(d, &n));
+    let q_mont = mont.montgomery_form(signed_mod(q, &n));
+
+    // n + 1 = k * 2^s, with k odd
+    let n_plus_one = n.wrapping_add(&T::one());
+    let (k, s) = if n_plus_one.is_zero() {
+        (T::one(), type_bits::<T>())
+    } else {
+        let s = n_plus_one.trailing_zeros() as usize;
+        (n_plus_one >> s, s)
+    };
+
+    // Compute U_k, V_k and Q^k from the top bit of k down.
+    let mut u = mont.one();
+    let mut v = mont.one();
+    let mut qk = q_mont;
+    for i in (0..k.bits() - 1).rev() {
+        // U_2j = U_j V_j, V_2j = V_j^2 - 2 Q^j
+        u = mont.mul(u, v);
+        v = ct_sub_mod(mont.square(v), ct_add_mod(qk, qk, n), n);
+        qk = mont.square(qk);
+        if (k >> i) & T::one() == T::one() {
+            // U_j+1 = (P U_j + V_j) / 2, V_j+1 = (D U_j + P V_j) / 2
+            let u_next = half_mod(ct_add_mod(u, v, n), &n);
+            v = half_mod(ct_add_mod(mont.mul(d_mont, u), v, n), &n);
+            u = u_next;
+            qk = mont.mul(qk, q_mont);
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        v = ct_sub_mod(mont.square(v), ct_add_mod(qk, qk, n), n);
+        if v.is_zero() {
+            return true;
+        }
+        qk = mont.square(qk);
+    }
+    false
+}
+
+/// Reduces the small signed `a` modulo `n`.
+fn signed_mod<T>(a: i64, n: &T) -> T
+where
+    T: UnsignedModularInt,
+{
+    let abs = a.unsigned_abs();
+    let magnitude = match <T as NumCast>::from(abs) {
+        Some(magnitude) => magnitude % *n,
+        // `n` is smaller than `a` here
+        None => <T as NumCast>::from(abs % n.to_u64().unwrap_or(1)).unwrap_or_else(T::zero),
+    };
+    if a < 0 && !magnitude.is_zero() {
+        *n - magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// Jacobi symbol `(a/n)` for a small signed `a` and an odd `n`.
+fn jacobi<T>(a: i64, n: &T) -> i8
+where
+    T: UnsignedModularInt,
+{
+    let n_mod_8 = (*n & <T as NumCast>::from(7u8).unwrap_or_else(T::zero))
+        .to_u8()
+        .unwrap_or(0);
+    let mut abs = a.unsigned_abs();
+    // (-1/n) = -1 iff n ≡ 3 mod 4
+    let mut result = if a < 0 && n_mod_8 % 4 == 3 { -1 } else { 1 };
+
+    if abs == 0 {
+        return if n.is_one() { 1 } else { 0 };
+    }
+    // (2/n) = -1 iff n ≡ 3 or 5 mod 8
+    while abs % 2 == 0 {
+        abs /= 2;
+        if n_mod_8 == 3 || n_mod_8 == 5 {
+            result = -result;
+        }
+    }
+
+    match <T as NumCast>::from(abs) {
+        Some(modulus) if modulus < *n => {
+            // Quadratic reciprocity: (a/n) = (n/a), negated iff a ≡ n ≡ 3 mod 4.
+            if abs % 4 == 3 && n_mod_8 % 4 == 3 {
+                result = -result;
+            }
+            result * jacobi_u64((*n % modulus).to_u64().unwrap_or(0), abs)
+        }
+        _ => result * jacobi_u64(abs, n.to_u64().unwrap_or(0)),
+    }
+}
+
+/// Jacobi symbol `(a/n)` for odd `n`.
+fn jacobi_u64(mut a: u64, mut n: u64) -> i8 {
+    let mut result = 1;
+    a %= n;
+    while a != 0 {
+        while a % 2 == 0 {
+            a /= 2;
+            if n % 8 == 3 || n % 8 == 5 {
+                result = -result;
+            }
+        }
+        core::mem::swap(&mut a, &mut n);
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+        a %= n;
+    }
+    if n == 1 {
+        result
+    } else {
+        0
+    }
+}
+
+/// Checks whether `n` is a perfect square.
+fn is_square<T>(n: &T) -> bool
+where
+    T: UnsignedModularInt,
+{
+    let root = isqrt(n);
+    root * root == *n
+}
+
+/// Integer square root, `floor(sqrt(n))`, with Newton's method.
+pub(crate) fn isqrt<T>(n: &T) -> T
+where
+    T: UnsignedModularInt,
+{
+    if n.is_zero() {
+        return T::zero();
+    }
+    let mut x = T::one() << ((n.bits() + 1) / 2);
+    loop {
+        let y = (x + *n / x) >> 1;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_prime(n: u64) -> bool {
+        n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0)
+    }
+
+    // Strong pseudoprimes to base 2
+    const SPSP2: [u64; 6] = [2047, 3277, 4033, 4681, 8321, 3_215_031_751];
+
+    // Strong Lucas pseudoprimes, Selfridge parameters
+    const SLPSP: [u64; 6] = [5459, 5777, 10877, 16109, 18971, 22499];
+
+    #[test]
+    fn test_miller_rabin() {
+        for n in 0..5000u64 {
+            let expected = is_prime(n) || SPSP2.contains(&n);
+            assert_eq!(miller_rabin(&n, &2), expected, "n = {}", n);
+        }
+        for n in SPSP2 {
+            assert!(miller_rabin(&n, &2));
+            assert!(!miller_rabin(&n, &13), "n = {}", n);
+        }
+        assert!(miller_rabin(&7u8, &14));
+        assert!(miller_rabin(&18_446_744_073_709_551_557_u64, &2));
+        assert!(!miller_rabin(&18_446_744_073_709_551_559_u64, &2));
+    }
+
+    #[test]
+    fn test_miller_rabin_deterministic() {
+        for n in 0..5000u64 {
+            assert_eq!(
+                miller_rabin_deterministic(&n),
+                Some(is_prime(n)),
+                "n = {}",
+                n
+            );
+        }
+        for n in SPSP2 {
+            assert_eq!(miller_rabin_deterministic(&n), Some(false), "n = {}", n);
+        }
+
+        // Strong pseudoprimes to bases 2 and 3
+        for n in [1_373_653_u64, 1_530_787, 1_987_021, 2_284_453, 3_116_107] {
+            assert!(miller_rabin(&n, &2) && miller_rabin(&n, &3));
+            assert_eq!(miller_rabin_deterministic(&n), Some(false), "n = {}", n);
+        }
+
+        // The smallest strong pseudoprime to the first 12 primes
+        let n = 318_665_857_834_031_151_167_461_u128;
+        assert!(miller_rabin(&n, &37));
+        assert_eq!(miller_rabin_deterministic(&n), Some(false));
+
+        assert_eq!(miller_rabin_deterministic(&7u8), Some(true));
+        assert_eq!(
+            miller_rabin_deterministic(&18_446_744_073_709_551_557_u64),
+            Some(true)
+        );
+        assert_eq!(
+            miller_rabin_deterministic(&18_446_744_073_709_551_559_u64),
+            Some(false)
+        );
+        assert_eq!(miller_rabin_deterministic(&(1u128 << 81)), None);
+        assert_eq!(
+            miller_rabin_deterministic(&((1u128 << 81) - 1)),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_strong_lucas() {
+        for n in 0..5000u64 {
+            assert_eq!(strong_lucas(&n), is_prime(n), "n = {}", n);
+        }
+        for n in SLPSP {
+            assert!(strong_lucas(&n), "n = {}", n);
+        }
+        for n in SPSP2 {
+            assert!(!strong_lucas(&n), "n = {}", n);
+        }
+        assert!(strong_lucas(&18_446_744_073_709_551_557_u64));
+        assert!(!strong_lucas(&u64::MAX));
+        assert!(!strong_lucas(&(65_521u64 * 65_521)));
+    }
+
+    #[test]
+    fn test_baillie_psw() {
+        for n in 0..20000u32 {
+            assert_eq!(baillie_psw(&n), is_prime(n as u64), "n = {}", n);
+        }
+        for n in SPSP2.iter().chain(SLPSP.iter()) {
+            assert!(!baillie_psw(n), "n = {}", n);
+        }
+        // Carmichael numbers
+        for n in [561u64, 41041, 825_265, 321_197_185, 5_394_826_801] {
+            assert!(!baillie_psw(&n), "n = {}", n);
+        }
+        assert!(baillie_psw(&251u8));
+        assert!(baillie_psw(&65_521u16));
+        assert!(baillie_psw(&18_446_744_073_709_551_557_u64));
+        assert!(!baillie_psw(&u64::MAX));
+        assert!(baillie_psw(&((1u128 << 127) - 1)));
+        assert!(!baillie_psw(&((1u128 << 127) + 1)));
+    }
+
+    #[test]
+    fn test_small_prime_residues() {
+        let value = 0xDEAD_BEEF_CAFE_BABE_u64;
+        let mut residues = [0u16; SMALL_PRIMES.len()];
+        small_prime_residues(&value, &mut residues);
+        for (r, p) in residues.iter().zip(SMALL_PRIMES.iter()) {
+            assert_eq!(*r as u64, value % *p as u64);
+        }
+    }
+
+    #[test]
+    fn test_jacobi() {
+        for n in (3..200u64).step_by(2) {
+            for a in -50..50i64 {
+                let expected = jacobi_u64(a.rem_euclid(n as i64) as u64, n);
+                assert_eq!(jacobi(a, &n), expected, "({}/{})", a, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_square() {
+        for n in 0..10_000u64 {
+            let root = (n as f64).sqrt() as u64;
+            assert_eq!(is_square(&n), root * root == n, "n = {}", n);
+        }
+        assert!(is_square(&(u32::MAX as u64 * u32::MAX as u64)));
+        assert!(!is_square(&u64::MAX));
+        assert!(is_square(&(251u8 as u16 * 251)));
+    }
+}
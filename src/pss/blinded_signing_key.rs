@@ -1,4 +1,4 @@
-use super::{get_pss_signature_algo_id, sign_digest, Signature, VerifyingKey};
+use super::{get_pss_signature_algo_id, sign_digest, SaltLength, Signature, VerifyingKey};
 use crate::{Result, RsaPrivateKey};
 use const_oid::AssociatedOid;
 use core::marker::PhantomData;
@@ -9,21 +9,21 @@ use signature::{
 };
 use zeroize::ZeroizeOnDrop;
 
-use crate::traits::UnsignedModularInt;
+use crate::traits::{PublicKeyParts, UnsignedModularInt};
 /// Signing key for producing "blinded" RSASSA-PSS signatures as described in
 /// [draft-irtf-cfrg-rsa-blind-signatures](https://datatracker.ietf.org/doc/draft-irtf-cfrg-rsa-blind-signatures/).
 #[derive(Debug, Clone)]
-pub struct BlindedSigningKey<D, T>
+pub struct BlindedSigningKey<D, T, const MAX_PRIMES: usize = 2>
 where
     D: Digest,
     T: UnsignedModularInt,
 {
-    inner: RsaPrivateKey<T>,
+    inner: RsaPrivateKey<T, MAX_PRIMES>,
     salt_len: usize,
     phantom: PhantomData<D>,
 }
 
-impl<D, T> BlindedSigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> BlindedSigningKey<D, T, MAX_PRIMES>
 where
     D: Digest,
     T: UnsignedModularInt,
@@ -31,13 +31,13 @@ where
     /// Create a new RSASSA-PSS signing key which produces "blinded"
     /// signatures.
     /// Digest output size is used as a salt length.
-    pub fn new(key: RsaPrivateKey<T>) -> Self {
+    pub fn new(key: RsaPrivateKey<T, MAX_PRIMES>) -> Self {
         Self::new_with_salt_len(key, <D as Digest>::output_size())
     }
 
     /// Create a new RSASSA-PSS signing key which produces "blinded"
     /// signatures with a salt of the given length.
-    pub fn new_with_salt_len(key: RsaPrivateKey<T>, salt_len: usize) -> Self {
+    pub fn new_with_salt_len(key: RsaPrivateKey<T, MAX_PRIMES>, salt_len: usize) -> Self {
         Self {
             inner: key,
             salt_len,
@@ -59,7 +59,11 @@ where
         bit_size: usize,
         salt_len: usize,
     ) -> Result<Self> {
-        todo!()
+        Ok(Self {
+            inner: RsaPrivateKey::new(rng, bit_size)?,
+            salt_len,
+            phantom: Default::default(),
+        })
     }
 
     /// Return specified salt length for this key
@@ -68,45 +72,131 @@ where
     }
 }
 
+impl<D, T, const MAX_PRIMES: usize> BlindedSigningKey<D, T, MAX_PRIMES>
+where
+    D: Digest + AssociatedOid,
+    T: UnsignedModularInt,
+{
+    /// Encodes the `AlgorithmIdentifier` of the signatures made with this
+    /// key, `id-RSASSA-PSS` with its parameters, into `storage`. Returns the
+    /// used part of `storage`.
+    pub fn signature_algorithm_identifier<'a>(&self, storage: &'a mut [u8]) -> Result<&'a [u8]> {
+        get_pss_signature_algo_id::<D>(self.salt_len, storage)
+    }
+}
+
+impl<D, T, const MAX_PRIMES: usize> BlindedSigningKey<D, T, MAX_PRIMES>
+where
+    D: Digest + FixedOutputReset,
+    T: UnsignedModularInt,
+{
+    /// Signs an already hashed message with a random salt taken from `rng`.
+    fn sign_hashed<R: CryptoRngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+        hashed: &[u8],
+    ) -> Result<Signature<T>> {
+        // The bytes of `n` are as long as the modulus, enough to hold the encoded message.
+        let mut storage = self.inner.n().to_be_bytes();
+        sign_digest::<_, D, _, MAX_PRIMES>(
+            rng,
+            true,
+            &self.inner,
+            hashed,
+            self.salt_len,
+            storage.as_mut(),
+        )
+    }
+}
+
 //
 // `*Signer` trait impls
 //
 
+impl<D, T, const MAX_PRIMES: usize> RandomizedDigestSigner<D, Signature<T>>
+    for BlindedSigningKey<D, T, MAX_PRIMES>
+where
+    D: Digest + FixedOutputReset,
+    T: UnsignedModularInt,
+{
+    fn try_sign_digest_with_rng(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        digest: D,
+    ) -> signature::Result<Signature<T>> {
+        self.sign_hashed(rng, &digest.finalize())
+            .map_err(|e| e.into())
+    }
+}
+
+impl<D, T, const MAX_PRIMES: usize> RandomizedSigner<Signature<T>>
+    for BlindedSigningKey<D, T, MAX_PRIMES>
+where
+    D: Digest + FixedOutputReset,
+    T: UnsignedModularInt,
+{
+    fn try_sign_with_rng(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        msg: &[u8],
+    ) -> signature::Result<Signature<T>> {
+        self.sign_hashed(rng, &D::digest(msg)).map_err(|e| e.into())
+    }
+}
+
+impl<D, T, const MAX_PRIMES: usize> RandomizedPrehashSigner<Signature<T>>
+    for BlindedSigningKey<D, T, MAX_PRIMES>
+where
+    D: Digest + FixedOutputReset,
+    T: UnsignedModularInt,
+{
+    fn sign_prehash_with_rng(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        prehash: &[u8],
+    ) -> signature::Result<Signature<T>> {
+        self.sign_hashed(rng, prehash).map_err(|e| e.into())
+    }
+}
+
 //
 // Other trait impls
 //
 
-impl<D, T> AsRef<RsaPrivateKey<T>> for BlindedSigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> AsRef<RsaPrivateKey<T, MAX_PRIMES>>
+    for BlindedSigningKey<D, T, MAX_PRIMES>
 where
     D: Digest,
     T: UnsignedModularInt,
 {
-    fn as_ref(&self) -> &RsaPrivateKey<T> {
+    fn as_ref(&self) -> &RsaPrivateKey<T, MAX_PRIMES> {
         &self.inner
     }
 }
 
-impl<D, T> From<RsaPrivateKey<T>> for BlindedSigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> From<RsaPrivateKey<T, MAX_PRIMES>>
+    for BlindedSigningKey<D, T, MAX_PRIMES>
 where
     D: Digest,
     T: UnsignedModularInt,
 {
-    fn from(key: RsaPrivateKey<T>) -> Self {
+    fn from(key: RsaPrivateKey<T, MAX_PRIMES>) -> Self {
         Self::new(key)
     }
 }
 
-impl<D, T> From<BlindedSigningKey<D, T>> for RsaPrivateKey<T>
+impl<D, T, const MAX_PRIMES: usize> From<BlindedSigningKey<D, T, MAX_PRIMES>>
+    for RsaPrivateKey<T, MAX_PRIMES>
 where
     D: Digest,
     T: UnsignedModularInt,
 {
-    fn from(key: BlindedSigningKey<D, T>) -> Self {
+    fn from(key: BlindedSigningKey<D, T, MAX_PRIMES>) -> Self {
         key.inner
     }
 }
 
-impl<D, T> Keypair for BlindedSigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> Keypair for BlindedSigningKey<D, T, MAX_PRIMES>
 where
     D: Digest,
     T: UnsignedModularInt,
@@ -115,20 +205,20 @@ where
     fn verifying_key(&self) -> Self::VerifyingKey {
         VerifyingKey {
             inner: self.inner.to_public_key(),
-            salt_len: self.salt_len,
+            salt_len: SaltLength::Exact(self.salt_len),
             phantom: Default::default(),
         }
     }
 }
 
-impl<D, T> ZeroizeOnDrop for BlindedSigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> ZeroizeOnDrop for BlindedSigningKey<D, T, MAX_PRIMES>
 where
     D: Digest,
     T: UnsignedModularInt,
 {
 }
 
-impl<D, T> PartialEq for BlindedSigningKey<D, T>
+impl<D, T, const MAX_PRIMES: usize> PartialEq for BlindedSigningKey<D, T, MAX_PRIMES>
 where
     D: Digest,
     T: UnsignedModularInt,
@@ -139,11 +229,4 @@ where
 }
 
 #[cfg(test)]
-mod tests {
-    #[test]
-    #[ignore]
-    #[cfg(feature = "serde")]
-    fn test_serde() {
-        todo!()
-    }
-}
+mod tests {}
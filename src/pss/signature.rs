@@ -1,7 +1,8 @@
 //! `RSASSA-PSS` signatures.
 
-use crate::algorithms::pad::uint_to_be_pad;
+use crate::algorithms::pad::{be_bytes_to_uint, uint_to_be_pad};
 use ::signature::SignatureEncoding;
+use core::borrow::Borrow;
 use core::fmt::{Debug, Display, Formatter, LowerHex, UpperHex};
 
 use crate::traits::UnsignedModularInt;
@@ -21,27 +22,49 @@ where
 impl<T> TryFrom<&[u8]> for Signature<T>
 where
     T: UnsignedModularInt,
-    <T as num_traits::FromBytes>::Bytes: num_traits::ops::bytes::NumBytes + Default,
 {
     type Error = signature::Error;
 
+    /// The signature may be shorter than `T`, e.g. for keys with a modulus
+    /// of 8n + 1 bits.
     fn try_from(bytes: &[u8]) -> signature::Result<Self> {
-        let mut bytes2 = <T as num_traits::FromBytes>::Bytes::default();
-        bytes2.as_mut().copy_from_slice(bytes);
-        let result = T::from_be_bytes(&bytes2);
         Ok(Self {
-            inner: result,
+            inner: be_bytes_to_uint(bytes).map_err(signature::Error::from)?,
             len: bytes.len(),
         })
     }
 }
 
+impl<T> Signature<T>
+where
+    T: UnsignedModularInt,
+{
+    /// Writes the `len` big-endian bytes of the signature as hex.
+    fn fmt_hex(&self, f: &mut Formatter<'_>, upper: bool) -> core::fmt::Result {
+        let bytes = self.inner.to_be_bytes();
+        let bytes: &[u8] = bytes.borrow();
+        for _ in bytes.len()..self.len {
+            write!(f, "00")?;
+        }
+        for byte in &bytes[bytes.len().saturating_sub(self.len)..] {
+            if upper {
+                write!(f, "{:02X}", byte)?;
+            } else {
+                write!(f, "{:02x}", byte)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<T> Debug for Signature<T>
 where
     T: UnsignedModularInt,
 {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> core::result::Result<(), core::fmt::Error> {
-        todo!()
+        fmt.debug_tuple("Signature")
+            .field(&format_args!("{:X}", self))
+            .finish()
     }
 }
 
@@ -50,7 +73,7 @@ where
     T: UnsignedModularInt,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        todo!()
+        self.fmt_hex(f, false)
     }
 }
 
@@ -59,7 +82,7 @@ where
     T: UnsignedModularInt,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        todo!()
+        self.fmt_hex(f, true)
     }
 }
 
@@ -71,3 +94,20 @@ where
         write!(f, "{:X}", self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_formatting() {
+        let sig = Signature {
+            inner: 0x0123_4567_89AB_CDEF_u64,
+            len: 9,
+        };
+        assert_eq!(format!("{:x}", sig), "000123456789abcdef");
+        assert_eq!(format!("{:X}", sig), "000123456789ABCDEF");
+        assert_eq!(format!("{}", sig), "000123456789ABCDEF");
+        assert_eq!(format!("{:?}", sig), "Signature(000123456789ABCDEF)");
+    }
+}
@@ -1,5 +1,7 @@
-use super::{verify_digest, Signature};
-use crate::RsaPublicKey;
+use super::{verify_digest, SaltLength, Signature};
+use crate::encoding::{pss_restriction, PssRestriction};
+use crate::errors::Error;
+use crate::{Result, RsaPublicKey};
 use const_oid::AssociatedOid;
 use core::marker::PhantomData;
 use digest::{Digest, FixedOutputReset};
@@ -18,7 +20,7 @@ where
     T: UnsignedModularInt,
 {
     pub(super) inner: RsaPublicKey<T>,
-    pub(super) salt_len: usize,
+    pub(super) salt_len: SaltLength,
     pub(super) phantom: PhantomData<D>,
 }
 
@@ -37,17 +39,80 @@ where
     pub fn new_with_salt_len(key: RsaPublicKey<T>, salt_len: usize) -> Self {
         Self {
             inner: key,
-            salt_len,
+            salt_len: SaltLength::Exact(salt_len),
+            phantom: Default::default(),
+        }
+    }
+
+    /// Create a new RSASSA-PSS verifying key.
+    /// The salt length is recovered from each signature.
+    pub fn new_with_auto_salt_len(key: RsaPublicKey<T>) -> Self {
+        Self::new_with_min_salt_len(key, 0)
+    }
+
+    /// Create a new RSASSA-PSS verifying key.
+    /// The salt length is recovered from each signature, which is rejected if
+    /// its salt is shorter than `min_len`.
+    pub fn new_with_min_salt_len(key: RsaPublicKey<T>, min_len: usize) -> Self {
+        Self {
+            inner: key,
+            salt_len: SaltLength::Auto { min_len },
             phantom: Default::default(),
         }
     }
 
     /// Return specified salt length for this key
-    pub fn salt_len(&self) -> usize {
+    pub fn salt_len(&self) -> SaltLength {
         self.salt_len
     }
 }
 
+impl<D, T> VerifyingKey<D, T>
+where
+    D: Digest + AssociatedOid,
+    T: UnsignedModularInt,
+{
+    /// Create a new RSASSA-PSS verifying key for a key restricted to the
+    /// parameters in `restriction`, see [`pss_restriction`].
+    ///
+    /// Fails with [`Error::InvalidPaddingScheme`] unless `D` is both the hash
+    /// algorithm and the MGF1 hash algorithm of the restriction. The salt
+    /// length is recovered from each signature, which is rejected if its salt
+    /// is shorter than the minimum salt length of the restriction.
+    pub fn new_with_restriction(
+        key: RsaPublicKey<T>,
+        restriction: &PssRestriction,
+    ) -> Result<Self> {
+        if restriction.hash.oid() != D::OID || restriction.mgf_hash.oid() != D::OID {
+            return Err(Error::InvalidPaddingScheme);
+        }
+        Ok(Self::new_with_min_salt_len(key, restriction.min_salt_len))
+    }
+}
+
+impl<D, T> VerifyingKey<D, T>
+where
+    D: Digest + FixedOutputReset,
+    T: UnsignedModularInt,
+{
+    /// Verify the signature of a prehashed message like
+    /// [`PrehashVerifier::verify_prehash`], returning the length of the salt
+    /// the signature was made with.
+    pub fn verify_prehash_and_get_salt_len(
+        &self,
+        prehash: &[u8],
+        signature: &Signature<T>,
+    ) -> Result<usize> {
+        verify_digest::<D, T>(
+            &self.inner,
+            prehash,
+            &signature.inner,
+            signature.len,
+            self.salt_len,
+        )
+    }
+}
+
 //
 // `*Verifier` trait impls
 //
@@ -65,6 +130,7 @@ where
             signature.len,
             self.salt_len,
         )
+        .map(|_| ())
         .map_err(|e| e.into())
     }
 }
@@ -82,6 +148,7 @@ where
             signature.len,
             self.salt_len,
         )
+        .map(|_| ())
         .map_err(|e| e.into())
     }
 }
@@ -99,6 +166,7 @@ where
             signature.len,
             self.salt_len,
         )
+        .map(|_| ())
         .map_err(|e| e.into())
     }
 }
@@ -142,6 +210,26 @@ where
     }
 }
 
+impl<D, T> TryFrom<pkcs8::SubjectPublicKeyInfoRef<'_>> for VerifyingKey<D, T>
+where
+    D: Digest + AssociatedOid,
+    T: UnsignedModularInt,
+{
+    type Error = pkcs8::spki::Error;
+
+    /// Keys with `RSASSA-PSS-params` are only accepted for the digest `D` and
+    /// enforce their restrictions, see [`VerifyingKey::new_with_restriction`].
+    fn try_from(spki: pkcs8::SubjectPublicKeyInfoRef<'_>) -> pkcs8::spki::Result<Self> {
+        let restriction = pss_restriction(&spki.algorithm)?;
+        let key = RsaPublicKey::try_from(spki)?;
+        match restriction {
+            Some(restriction) => Self::new_with_restriction(key, &restriction)
+                .or(Err(pkcs8::spki::Error::KeyMalformed)),
+            None => Ok(Self::new(key)),
+        }
+    }
+}
+
 impl<D, T> From<VerifyingKey<D, T>> for RsaPublicKey<T>
 where
     D: Digest,
@@ -0,0 +1,389 @@
+//! Verification of signatures selected by an X.509 `AlgorithmIdentifier`.
+//!
+//! Certificates, CRLs and CMS structures name the algorithm of a signature
+//! with an `AlgorithmIdentifier` next to it. [`SignatureAlgorithm`] decodes
+//! the RSA ones, picking the digest and padding scheme, so the signature can
+//! be verified without dispatching on the OID by hand.
+
+use pkcs8::der::asn1::{AnyRef, Null};
+use pkcs8::der::Decode;
+use pkcs8::spki::{self, AlgorithmIdentifierRef};
+
+use crate::encoding::{ID_MGF_1, ID_RSASSA_PSS};
+use crate::errors::{Error, Result};
+use crate::traits::UnsignedModularInt;
+use crate::{HashAlgorithm, Pkcs1v15Sign, Pss, RsaPublicKey};
+
+// Maximum supported length of digests
+const MAX_DIGEST_LEN: usize = 64;
+
+/// Verifies `sig` over `msg` with the signature algorithm given by the DER
+/// encoded `AlgorithmIdentifier` in `algorithm`.
+///
+/// See [`SignatureAlgorithm`] for the supported algorithms.
+pub fn verify<T>(pub_key: &RsaPublicKey<T>, algorithm: &[u8], msg: &[u8], sig: &[u8]) -> Result<()>
+where
+    T: UnsignedModularInt,
+{
+    SignatureAlgorithm::from_der(algorithm)?.verify(pub_key, msg, sig)
+}
+
+/// RSA signature algorithm decoded from an `AlgorithmIdentifier`.
+///
+/// Supports the `sha*WithRSAEncryption` and `id-rsassa-pkcs1-v1_5-with-sha3-*`
+/// algorithms for each enabled [`HashAlgorithm`], as well as `id-RSASSA-PSS`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SignatureAlgorithm {
+    /// RSASSA-PKCS1-v1_5 with the given hash algorithm.
+    Pkcs1v15(HashAlgorithm),
+
+    /// RSASSA-PSS with the given parameters.
+    Pss(PssParams),
+}
+
+impl SignatureAlgorithm {
+    /// Decodes the signature algorithm from a DER encoded `AlgorithmIdentifier`.
+    pub fn from_der(bytes: &[u8]) -> Result<Self> {
+        Self::try_from(AlgorithmIdentifierRef::from_der(bytes)?)
+    }
+
+    /// Hash algorithm of the signed message.
+    pub fn hash(&self) -> HashAlgorithm {
+        match self {
+            Self::Pkcs1v15(hash) => *hash,
+            Self::Pss(params) => params.hash,
+        }
+    }
+
+    /// Verifies `sig` over `msg`, which is hashed with [`Self::hash`].
+    pub fn verify<T>(&self, pub_key: &RsaPublicKey<T>, msg: &[u8], sig: &[u8]) -> Result<()>
+    where
+        T: UnsignedModularInt,
+    {
+        let mut hashed = [0u8; MAX_DIGEST_LEN];
+        let len = self.hash().with_digest(|digest| {
+            let len = digest.output_size();
+            digest.update(msg);
+            digest
+                .finalize_into_reset(hashed.get_mut(..len).ok_or(Error::DigestBufferTooSmall)?)
+                .or(Err(Error::DigestBufferTooSmall))?;
+            Ok(len)
+        })?;
+        self.verify_prehash(pub_key, &hashed[..len], sig)
+    }
+
+    /// Verifies `sig` over the digest `hashed` of a message.
+    pub fn verify_prehash<T>(
+        &self,
+        pub_key: &RsaPublicKey<T>,
+        hashed: &[u8],
+        sig: &[u8],
+    ) -> Result<()>
+    where
+        T: UnsignedModularInt,
+    {
+        match self {
+            Self::Pkcs1v15(hash) => {
+                pub_key.verify(Pkcs1v15Sign::from_hash_algorithm(*hash), hashed, sig)
+            }
+            Self::Pss(params) => pub_key.verify(params.to_pss()?, hashed, sig),
+        }
+    }
+}
+
+impl TryFrom<AlgorithmIdentifierRef<'_>> for SignatureAlgorithm {
+    type Error = Error;
+
+    fn try_from(algorithm: AlgorithmIdentifierRef<'_>) -> Result<Self> {
+        if algorithm.oid == ID_RSASSA_PSS {
+            let params = algorithm
+                .parameters
+                .ok_or(spki::Error::AlgorithmParametersMissing)?;
+            return Ok(Self::Pss(PssParams::try_from(params)?));
+        }
+
+        let hash = HashAlgorithm::from_pkcs1v15_oid(algorithm.oid)
+            .ok_or(spki::Error::OidUnknown { oid: algorithm.oid })?;
+        check_null_parameters(algorithm.parameters)?;
+        Ok(Self::Pkcs1v15(hash))
+    }
+}
+
+/// Parameters of an RSASSA-PSS signature, as in the `RSASSA-PSS-params` of
+/// [RFC8017 Appendix A.2.3].
+///
+/// [RFC8017 Appendix A.2.3]: https://datatracker.ietf.org/doc/html/rfc8017#appendix-A.2.3
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PssParams {
+    /// Hash algorithm of the signed message.
+    pub hash: HashAlgorithm,
+
+    /// Hash algorithm used by MGF1.
+    pub mgf_hash: HashAlgorithm,
+
+    /// Salt length.
+    pub salt_len: usize,
+}
+
+impl PssParams {
+    /// Decodes DER encoded `RSASSA-PSS-params`.
+    pub fn from_der(bytes: &[u8]) -> Result<Self> {
+        Self::try_from(pkcs1::RsaPssParams::from_der(bytes)?)
+    }
+
+    /// Creates the [`Pss`] padding scheme for these parameters.
+    ///
+    /// The padding schemes of this crate use the same digest for MGF1 as for
+    /// the message, so other MGF1 hash algorithms are rejected with
+    /// [`Error::InvalidPaddingScheme`].
+    pub fn to_pss(&self) -> Result<Pss> {
+        if self.mgf_hash != self.hash {
+            return Err(Error::InvalidPaddingScheme);
+        }
+        Ok(Pss {
+            salt_len: self.salt_len,
+            ..Pss::from_hash_algorithm(self.hash)
+        })
+    }
+}
+
+impl TryFrom<AnyRef<'_>> for PssParams {
+    type Error = Error;
+
+    fn try_from(params: AnyRef<'_>) -> Result<Self> {
+        Self::try_from(params.decode_as::<pkcs1::RsaPssParams<'_>>()?)
+    }
+}
+
+impl TryFrom<pkcs1::RsaPssParams<'_>> for PssParams {
+    type Error = Error;
+
+    fn try_from(params: pkcs1::RsaPssParams<'_>) -> Result<Self> {
+        let hash = digest_algorithm(params.hash)?;
+
+        if params.mask_gen.oid != ID_MGF_1 {
+            return Err(spki::Error::OidUnknown {
+                oid: params.mask_gen.oid,
+            }
+            .into());
+        }
+        let mgf_hash = params
+            .mask_gen
+            .parameters
+            .ok_or(spki::Error::AlgorithmParametersMissing)?;
+        let mgf_hash = digest_algorithm(mgf_hash)?;
+
+        // `TrailerField` can only be trailerFieldBC, the 0xbc trailer used by
+        // this crate, so `params.trailer_field` needs no check
+        Ok(Self {
+            hash,
+            mgf_hash,
+            salt_len: params.salt_len.into(),
+        })
+    }
+}
+
+/// Looks up the hash algorithm of a digest `AlgorithmIdentifier`.
+fn digest_algorithm(algorithm: AlgorithmIdentifierRef<'_>) -> Result<HashAlgorithm> {
+    let hash = HashAlgorithm::from_oid(algorithm.oid)
+        .ok_or(spki::Error::OidUnknown { oid: algorithm.oid })?;
+    check_null_parameters(algorithm.parameters)?;
+    Ok(hash)
+}
+
+/// Checks that the parameters of an `AlgorithmIdentifier` are either NULL or
+/// absent, both of which are found in the wild.
+fn check_null_parameters(parameters: Option<AnyRef<'_>>) -> Result<()> {
+    if let Some(parameters) = parameters {
+        parameters.decode_as::<Null>()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RsaPrivateKey;
+
+    use fixed_bigint::FixedUInt;
+    use hex_literal::hex;
+    use num_traits::{FromBytes, NumCast};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use sha2::{Digest, Sha256};
+
+    type U512 = FixedUInt<u32, 16>;
+
+    fn get_private_key() -> RsaPrivateKey<U512> {
+        RsaPrivateKey::from_components(
+            U512::from_be_bytes(&hex!(
+                "b2990f49c47dfa8cd400ae6a4d1b8a3b6a13642b23f28b003bfb97790ade9a4c"
+                "c82b8b2a81747ddec08b6296e53a08c331687ef25c4bf4936ba1c0e6041e9d15"
+            )),
+            <U512 as NumCast>::from(65537).unwrap(),
+            U512::from_be_bytes(&hex!(
+                "8abd6a69f4d1a4b487f0ab8d7aaefd38609405c999984e30f567e1e8aeeff44e"
+                "8b18bdb1ec78dfa31a55e32a48d7fb131f5af1f44d7d6b2ced2a9df5e5ae4535"
+            )),
+            &[
+                U512::from_be_bytes(&hex!(
+                    "dab2f18048baa68de7df04d2d35d5d80e60e2dfa42d50a9b04219032715e46b3"
+                )),
+                U512::from_be_bytes(&hex!(
+                    "d10f2e66b1d0c13f10ef9927bf5324a379ca218146cbf9cafc795221f16a3117"
+                )),
+            ],
+        )
+        .unwrap()
+    }
+
+    const MSG: &[u8] = b"Test.\n";
+
+    // sha256WithRSAEncryption, with and without the NULL parameters
+    const SHA256_WITH_RSA: &[u8] = &hex!("300d06092a864886f70d01010b0500");
+    const SHA256_WITH_RSA_NO_NULL: &[u8] = &hex!("300b06092a864886f70d01010b");
+
+    // id-RSASSA-PSS with SHA-256, MGF1 with SHA-256 and a salt of 20 bytes
+    const PSS_SHA256: &[u8] = &hex!(
+        "3041"
+        "06092a864886f70d01010a"
+        "3034"
+        "a00f300d06096086480165030402010500"
+        "a11c301a06092a864886f70d010108300d06096086480165030402010500"
+        "a203020114"
+    );
+
+    #[test]
+    fn test_decode_signature_algorithm() {
+        assert_eq!(
+            SignatureAlgorithm::from_der(SHA256_WITH_RSA),
+            Ok(SignatureAlgorithm::Pkcs1v15(HashAlgorithm::Sha256))
+        );
+        assert_eq!(
+            SignatureAlgorithm::from_der(SHA256_WITH_RSA_NO_NULL),
+            Ok(SignatureAlgorithm::Pkcs1v15(HashAlgorithm::Sha256))
+        );
+        assert_eq!(
+            SignatureAlgorithm::from_der(PSS_SHA256),
+            Ok(SignatureAlgorithm::Pss(PssParams {
+                hash: HashAlgorithm::Sha256,
+                mgf_hash: HashAlgorithm::Sha256,
+                salt_len: 20,
+            }))
+        );
+
+        // rsaEncryption is not a signature algorithm
+        assert!(SignatureAlgorithm::from_der(&hex!("300d06092a864886f70d0101010500")).is_err());
+        // Parameters other than NULL
+        assert!(SignatureAlgorithm::from_der(&hex!("300e06092a864886f70d01010b020100")).is_err());
+        // id-RSASSA-PSS without parameters
+        assert!(SignatureAlgorithm::from_der(&hex!("300b06092a864886f70d01010a")).is_err());
+        // Trailing garbage
+        assert!(SignatureAlgorithm::from_der(&hex!("300d06092a864886f70d01010b050000")).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "sha1")]
+    fn test_decode_pss_default_params() {
+        // All fields of RSASSA-PSS-params have SHA-1 based defaults
+        assert_eq!(
+            SignatureAlgorithm::from_der(&hex!("300d06092a864886f70d01010a3000")),
+            Ok(SignatureAlgorithm::Pss(PssParams {
+                hash: HashAlgorithm::Sha1,
+                mgf_hash: HashAlgorithm::Sha1,
+                salt_len: 20,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_verify_pkcs1v15() {
+        let priv_key = get_private_key();
+        let pub_key = priv_key.to_public_key();
+        let mut storage = [0u8; 64];
+
+        let scheme = Pkcs1v15Sign::from_hash_algorithm(HashAlgorithm::Sha256);
+        let sig = priv_key
+            .sign(scheme, &Sha256::digest(MSG), &mut storage)
+            .unwrap();
+        let mut sig_bytes = [0u8; 64];
+        sig_bytes.copy_from_slice(sig);
+
+        assert_eq!(verify(&pub_key, SHA256_WITH_RSA, MSG, &sig_bytes), Ok(()));
+        assert_eq!(
+            verify(&pub_key, SHA256_WITH_RSA_NO_NULL, MSG, &sig_bytes),
+            Ok(())
+        );
+        assert_eq!(
+            verify(&pub_key, SHA256_WITH_RSA, b"Test!\n", &sig_bytes),
+            Err(Error::Verification)
+        );
+        assert_eq!(
+            verify(&pub_key, PSS_SHA256, MSG, &sig_bytes),
+            Err(Error::Verification)
+        );
+        assert_eq!(
+            SignatureAlgorithm::Pkcs1v15(HashAlgorithm::Sha256).verify_prehash(
+                &pub_key,
+                &Sha256::digest(MSG),
+                &sig_bytes
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_verify_pss() {
+        let priv_key = get_private_key();
+        let pub_key = priv_key.to_public_key();
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        let mut storage = [0u8; 64];
+
+        let scheme = Pss {
+            salt_len: 20,
+            ..Pss::from_hash_algorithm(HashAlgorithm::Sha256)
+        };
+        let sig = priv_key
+            .sign_with_rng(&mut rng, scheme, &Sha256::digest(MSG), &mut storage)
+            .unwrap();
+        let mut sig_bytes = [0u8; 64];
+        sig_bytes.copy_from_slice(sig);
+
+        assert_eq!(verify(&pub_key, PSS_SHA256, MSG, &sig_bytes), Ok(()));
+        assert_eq!(
+            verify(&pub_key, PSS_SHA256, b"Test!\n", &sig_bytes),
+            Err(Error::Verification)
+        );
+        assert_eq!(
+            verify(&pub_key, SHA256_WITH_RSA, MSG, &sig_bytes),
+            Err(Error::Verification)
+        );
+
+        // A signature made by OpenSSL
+        let sig = hex!(
+            "4d0d51fa3da716f8e4f17e65e36445e7ba142d133cb53a72c2c178fbd988b645"
+            "22c965dd137b195fc807b46e00e4416fb0494b08d2ebb34746d11c2ab8bc89f3"
+        );
+        assert_eq!(verify(&pub_key, PSS_SHA256, MSG, &sig), Ok(()));
+        assert_eq!(
+            SignatureAlgorithm::from_der(PSS_SHA256)
+                .unwrap()
+                .verify_prehash(&pub_key, &Sha256::digest(MSG), &sig),
+            Ok(())
+        );
+        assert_eq!(
+            verify(&pub_key, PSS_SHA256, b"Test!\n", &sig),
+            Err(Error::Verification)
+        );
+
+        // MGF1 with another digest than the message is not supported
+        let params = PssParams {
+            hash: HashAlgorithm::Sha256,
+            mgf_hash: HashAlgorithm::Sha384,
+            salt_len: 20,
+        };
+        assert_eq!(
+            SignatureAlgorithm::Pss(params).verify(&pub_key, MSG, &sig_bytes),
+            Err(Error::InvalidPaddingScheme)
+        );
+    }
+}
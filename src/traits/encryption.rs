@@ -15,10 +15,28 @@ pub trait RandomizedEncryptor {
 }
 
 /// Decrypt the given message
-pub trait Decryptor {}
+pub trait Decryptor {
+    /// Decrypt the given message.
+    ///
+    /// `storage` must be at least as long as the modulus. The returned
+    /// plaintext is a part of it.
+    fn decrypt<'a>(&self, ciphertext: &[u8], storage: &'a mut [u8]) -> Result<&'a [u8]>;
+}
 
 /// Decrypt the given message using provided random source
-pub trait RandomizedDecryptor {}
+pub trait RandomizedDecryptor {
+    /// Decrypt the given message, using RSA blinding with the provided random
+    /// source.
+    ///
+    /// `storage` must be at least as long as the modulus. The returned
+    /// plaintext is a part of it.
+    fn decrypt_with_rng<'a, R: CryptoRngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+        ciphertext: &[u8],
+        storage: &'a mut [u8],
+    ) -> Result<&'a [u8]>;
+}
 
 /// Encryption keypair with an associated encryption key.
 pub trait EncryptingKeypair {
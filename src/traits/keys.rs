@@ -1,6 +1,6 @@
 //! Traits related to the key components
 
-use num_traits::{Num, One, PrimInt, Signed, Unsigned, Zero};
+use num_traits::{Num, One, PrimInt, Unsigned, Zero};
 use zeroize::Zeroize;
 
 use crate::traits::modular::UnsignedModularInt;
@@ -11,7 +11,7 @@ where
     T: UnsignedModularInt,
 {
     /// Returns the modulus of the key.
-    fn n(&self) -> &T;
+    fn n(&self) -> T;
 
     /// Returns the public exponent of the key.
     fn e(&self) -> &T;
@@ -28,8 +28,9 @@ pub trait PrivateKeyParts<T>: PublicKeyParts<T>
 where
     T: UnsignedModularInt,
 {
-    /// Returns the private exponent of the key.
-    fn d(&self) -> &T;
+    /// Returns the private exponent of the key, or `None` if the key only
+    /// holds the CRT values.
+    fn d(&self) -> Option<&T>;
 
     /// Returns the prime factors.
     fn primes(&self) -> &[T];
@@ -41,17 +42,15 @@ where
     fn dq(&self) -> Option<&T>;
 
     /// Returns the precomputed qinv value, Q^-1 mod P.
-    /// Since qinv can be negative, we use Signed trait here.
-    fn qinv(&self) -> Option<&T>
-    where
-        T: Signed;
+    fn qinv(&self) -> Option<&T>;
 
     /// Returns an iterator over the CRT Values
     fn crt_values(&self) -> Option<&[CrtValue<T>]>;
 }
 
 /// Contains the precomputed Chinese remainder theorem values.
-// TODO : THIS CAN BE NEGATIVE / SIGNED
+///
+/// All values are stored reduced, so they are never negative.
 #[derive(Debug, Clone)]
 pub struct CrtValue<T>
 where
@@ -59,10 +58,9 @@ where
 {
     /// D mod (prime - 1)
     pub(crate) exp: T,
-    /// R·Coeff ≡ 1 mod Prime.
+    /// R·Coeff ≡ 1 mod Prime, where R is the product of the primes prior to
+    /// this one. R itself is derived from the primes when needed.
     pub(crate) coeff: T,
-    /// product of primes prior to this (inc p and q)
-    pub(crate) r: T,
 }
 
 impl<T> Zeroize for CrtValue<T>
@@ -72,7 +70,6 @@ where
     fn zeroize(&mut self) {
         self.exp.zeroize();
         self.coeff.zeroize();
-        self.r.zeroize();
     }
 }
 
@@ -91,10 +88,6 @@ mod tests {
 
     #[test]
     fn test_count_bits() {
-        let _crt = CrtValue::<u64> {
-            exp: 0,
-            coeff: 0,
-            r: 0,
-        };
+        let _crt = CrtValue::<u64> { exp: 0, coeff: 0 };
     }
 }
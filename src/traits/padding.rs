@@ -15,8 +15,16 @@ where
     ///
     /// If an `rng` is passed, it uses RSA blinding to help mitigate timing
     /// side-channel attacks.
-
-    // Decrypt function
+    ///
+    /// `storage` must be at least as long as the modulus. The returned
+    /// plaintext is a part of it.
+    fn decrypt<'a, Rng: CryptoRngCore, const MAX_PRIMES: usize>(
+        self,
+        rng: Option<&mut Rng>,
+        priv_key: &RsaPrivateKey<T, MAX_PRIMES>,
+        ciphertext: &[u8],
+        storage: &'a mut [u8],
+    ) -> Result<&'a [u8]>;
 
     /// Encrypt the given message using the given public key.
     fn encrypt<'a, Rng: CryptoRngCore>(
@@ -34,8 +42,16 @@ where
     T: UnsignedModularInt + Clone,
 {
     /// Sign the given digest.
-
-    // Sign function
+    ///
+    /// The signature is written to `storage`, which must be at least as long
+    /// as the modulus, and the signature part of it is returned.
+    fn sign<'a, Rng: CryptoRngCore, const MAX_PRIMES: usize>(
+        self,
+        rng: Option<&mut Rng>,
+        priv_key: &RsaPrivateKey<T, MAX_PRIMES>,
+        hashed: &[u8],
+        storage: &'a mut [u8],
+    ) -> Result<&'a [u8]>;
 
     /// Verify a signed message.
     ///